@@ -1,22 +1,209 @@
-use irc::client::Client;
-use ratatui::widgets::ListState;
-use std::error::Error;
+use chrono::{DateTime, Local};
+use irc::{
+    client::Client,
+    proto::{message::Tag, Command, Message},
+};
+use ratatui::{
+    layout::{Direction, Rect},
+    widgets::ListState,
+};
+use std::{error::Error, io, time::Duration};
+use tokio::sync::mpsc::UnboundedSender;
 use tokio_util::sync::CancellationToken;
 
-use crate::{join_input::JoinBox, messagebox::MessageBox};
+use crate::{
+    chatters::{ChatterList, ChatterPanel, Role, UserCard},
+    clipboard,
+    completion::{
+        candidates, Completion, CompletionContext, CompletionSources, MODERATION_COMMANDS,
+    },
+    event::Event,
+    filters::{FilterRule, Filtered, Filters, Ignore},
+    history::InputHistory,
+    join_input::JoinBox,
+    keymap::Keymaps,
+    logger::ChatLogger,
+    messagebox::{MessageBox, MessageMode},
+    panes::PaneLayout,
+    search::{BufferSearch, SearchOverlay},
+    settings::{normalize_channel, Settings},
+    stats::Stats,
+    store::{MessageStore, SearchQuery},
+    twitch::{
+        client_stream::Tags,
+        helix::{HelixClient, HelixEvent},
+    },
+};
 
 pub type AppResult<T> = std::result::Result<T, Box<dyn Error>>;
 
-#[derive(Default, PartialEq, Eq)]
+/// The parts of the irc `Client` the app talks to, so it can run without a connection.
+pub trait ChatClient {
+    fn send(&self, message: Message) -> irc::error::Result<()>;
+    fn send_join(&self, channel: &str) -> irc::error::Result<()>;
+    fn send_part(&self, channel: &str) -> irc::error::Result<()>;
+    fn send_privmsg(&self, target: &str, message: &str) -> irc::error::Result<()>;
+    fn current_nickname(&self) -> &str;
+}
+
+impl ChatClient for Client {
+    fn send(&self, message: Message) -> irc::error::Result<()> {
+        Client::send(self, message)
+    }
+
+    fn send_join(&self, channel: &str) -> irc::error::Result<()> {
+        Client::send_join(self, channel)
+    }
+
+    fn send_part(&self, channel: &str) -> irc::error::Result<()> {
+        Client::send_part(self, channel)
+    }
+
+    fn send_privmsg(&self, target: &str, message: &str) -> irc::error::Result<()> {
+        Client::send_privmsg(self, target, message)
+    }
+
+    fn current_nickname(&self) -> &str {
+        Client::current_nickname(self)
+    }
+}
+
+// how many rows a single `/search` returns
+const SEARCH_LIMIT: usize = 200;
+
+// how many stored messages the user card loads
+const USER_CARD_HISTORY: usize = 500;
+
+// seconds a timeout from the user card lasts unless edited
+const DEFAULT_TIMEOUT: u32 = 600;
+
+#[derive(Default, PartialEq, Eq, Clone)]
 pub struct MessageInfo {
+    // twitch message id, or a locally generated one for messages we sent
+    pub id: String,
+    pub channel: String,
+    pub user_id: String,
+    pub nickname: String,
+    pub content: String,
+    pub timestamp: DateTime<Local>,
+    pub reply_parent: Option<ReplyParent>,
+    // bits cheered with the message, 0 for plain chat
+    pub bits: u64,
+    pub arrival: Arrival,
+    // set from the ignores and filter rules when added, and again when they change
+    pub filter: Filtered,
+    // id of the earlier message this one repeats, drawn folded into it
+    pub repeat_of: Option<String>,
+    // how many later messages were folded into this one
+    pub repeats: usize,
+}
+
+/// The message a reply answers, from twitch's `reply-parent-*` tags.
+#[derive(Default, PartialEq, Eq, Clone, Debug)]
+pub struct ReplyParent {
+    pub id: String,
+    // first message of the conversation, the same as `id` for a direct reply
+    pub thread_id: String,
     pub nickname: String,
     pub content: String,
 }
 
+/// Whether a message is someone's first in the channel, from twitch's
+/// `first-msg` and `returning-chatter` tags.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Arrival {
+    #[default]
+    Regular,
+    // the first message they ever sent in the channel
+    FirstMessage,
+    // back after a while away
+    Returning,
+}
+
+// how many left channels can be reopened
+const CLOSED_CHANNELS: usize = 10;
+
+// how many chatters per channel are remembered for completion
+const RECENT_CHATTERS: usize = 200;
+
+// how far back a message looks for one it repeats
+const REPEAT_LOOKBACK: usize = 50;
+
+// what two messages have to share to count as repeats: case, spacing,
+// repeated words and twitch's invisible duplicate-bypass character don't matter
+fn repeat_key(content: &str) -> String {
+    let mut words: Vec<String> = content
+        .split_whitespace()
+        .map(|word| word.replace('\u{e0000}', "").to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.dedup();
+    words.join(" ")
+}
+
+/// What a tab shows, a joined channel or messages gathered from all of them.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ChannelKind {
+    Channel,
+    // every message from every joined channel
+    AllChannels,
+    // messages that mention us
+    Mentions,
+    // messages containing one of the configured highlight words
+    Highlights,
+    // first messages of new chatters
+    FirstChatters,
+    // a whisper conversation with one user, named `@nick`
+    Whisper,
+}
+
+impl ChannelKind {
+    /// Whether the tab gathers messages from the joined channels.
+    pub fn is_virtual(&self) -> bool {
+        matches!(
+            self,
+            ChannelKind::AllChannels
+                | ChannelKind::Mentions
+                | ChannelKind::Highlights
+                | ChannelKind::FirstChatters
+        )
+    }
+
+    /// The kind of virtual tab called `name`, such as `[all]`.
+    pub fn from_tab_name(name: &str) -> Option<Self> {
+        [
+            ChannelKind::AllChannels,
+            ChannelKind::Mentions,
+            ChannelKind::Highlights,
+            ChannelKind::FirstChatters,
+        ]
+        .into_iter()
+        .find(|kind| kind.tab_name() == name)
+    }
+
+    fn tab_name(&self) -> &'static str {
+        match self {
+            ChannelKind::Channel | ChannelKind::Whisper => "",
+            ChannelKind::AllChannels => "[all]",
+            ChannelKind::Mentions => "[mentions]",
+            ChannelKind::Highlights => "[highlights]",
+            ChannelKind::FirstChatters => "[first]",
+        }
+    }
+}
+
 #[derive(PartialEq, Eq)]
 pub struct ChannelInfo {
     pub name: String,
+    pub kind: ChannelKind,
     pub messages: Vec<MessageInfo>,
+    // most recently active first
+    pub recent_chatters: Vec<String>,
+    // channel a virtual tab sends to, picked on the first send
+    pub send_target: Option<String>,
+    pub chatters: ChatterList,
+    // whispers received since the tab was last looked at
+    pub unread: usize,
 }
 
 #[derive(Default)]
@@ -24,15 +211,132 @@ pub enum AppMode {
     #[default]
     Normal,
     Joining,
+    Search,
+    BufferSearch,
+    Help,
+    ConfirmLeave,
+    UserCard,
+    ChooseTarget,
+    Chatters,
+    Moderate,
+    ConfirmBan,
+    Thread,
+    Filters,
+}
+
+/// Where clickable things were drawn on the last frame.
+#[derive(Default)]
+pub struct ClickAreas {
+    pub messages: Rect,
+    pub panes: Vec<Rect>,
+    pub tabs: Vec<Rect>,
+    pub nicknames: Vec<(Rect, String)>,
+}
+
+impl MessageInfo {
+    /// Whether near-identical messages can be folded into this one, or it into them.
+    pub fn is_foldable(&self) -> bool {
+        self.bits == 0 && self.arrival == Arrival::Regular
+    }
+
+    /// The message as it is shown in the messages list.
+    pub fn display_text(&self) -> String {
+        format!("{}: {}", self.nickname, self.content)
+    }
+}
+
+/// Quick actions offered by the user card.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UserCardCommand {
+    Mention,
+    Whisper,
+    Ignore,
+    Timeout,
+    Ban,
+}
+
+// the broadcaster's login is the channel name
+fn is_broadcaster(channel: &str, nickname: &str) -> bool {
+    channel
+        .trim_start_matches('#')
+        .eq_ignore_ascii_case(nickname)
+}
+
+/// Id for a message twitch didn't give us one for, such as our own.
+pub fn local_message_id() -> String {
+    format!(
+        "local-{}",
+        Local::now().timestamp_nanos_opt().unwrap_or_default()
+    )
 }
 
 impl ChannelInfo {
     pub fn new(name: String) -> Self {
         Self {
             name,
+            kind: ChannelKind::Channel,
             messages: Vec::new(),
+            recent_chatters: Vec::new(),
+            send_target: None,
+            chatters: ChatterList::default(),
+            unread: 0,
+        }
+    }
+
+    pub fn whisper(nickname: &str) -> Self {
+        Self {
+            kind: ChannelKind::Whisper,
+            ..Self::new(format!("@{}", nickname.to_lowercase()))
+        }
+    }
+
+    pub fn virtual_tab(kind: ChannelKind) -> Self {
+        Self {
+            kind,
+            ..Self::new(kind.tab_name().to_string())
+        }
+    }
+
+    /// Adds a message, folding it into a near-identical one said less than `window` seconds before.
+    fn push_message(&mut self, mut message: MessageInfo, window: u64) {
+        let key = repeat_key(&message.content);
+        // cheers and arrivals have a line of their own that folding would hide
+        if window > 0 && !key.is_empty() && message.is_foldable() {
+            let since = message.timestamp - chrono::Duration::seconds(window as i64);
+            let head = self
+                .messages
+                .iter_mut()
+                .rev()
+                .take(REPEAT_LOOKBACK)
+                .take_while(|m| m.timestamp >= since)
+                .find(|m| {
+                    m.repeat_of.is_none()
+                        && m.is_foldable()
+                        && m.filter == message.filter
+                        && repeat_key(&m.content) == key
+                });
+            if let Some(head) = head {
+                head.repeats += 1;
+                message.repeat_of = Some(head.id.clone());
+            }
+        }
+        self.messages.push(message);
+    }
+
+    // folds every message again, after filters changed which ones are shown
+    fn refold(&mut self, window: u64) {
+        for mut message in std::mem::take(&mut self.messages) {
+            message.repeat_of = None;
+            message.repeats = 0;
+            self.push_message(message, window);
         }
     }
+
+    fn saw_chatter(&mut self, nickname: &str) {
+        self.recent_chatters.retain(|nick| nick != nickname);
+        self.recent_chatters.insert(0, nickname.to_string());
+        self.recent_chatters.truncate(RECENT_CHATTERS);
+    }
 }
 
 pub struct App {
@@ -40,65 +344,973 @@ pub struct App {
     pub message_box: MessageBox,
     pub join_box: JoinBox,
     pub channels: Vec<ChannelInfo>,
+    // channels that were left, most recent last, with their scrollback
+    pub closed_channels: Vec<ChannelInfo>,
+    // channel and scroll position of the focused pane, the others keep theirs in `panes`
     pub current_channel: usize,
     pub list_state: ListState,
+    pub panes: PaneLayout,
+    // channel the focused pane of a saved layout is waiting to join
+    focus_on_join: Option<String>,
+    // notices from before any tab was open, shown in the first one
+    pending_notices: Vec<String>,
     pub app_mode: AppMode,
-    client: Client,
+    pub search: SearchOverlay,
+    pub buffer_search: BufferSearch,
+    // message to scroll into view on the next render
+    pub scroll_to_message: Option<String>,
+    // message drawn highlighted in the messages list
+    pub focused_message: Option<String>,
+    // messages whose folded repeats are drawn one by one
+    pub expanded_repeats: Vec<String>,
+    pub history: InputHistory,
+    pub keymaps: Keymaps,
+    // rows the help overlay is scrolled down by
+    pub help_scroll: u16,
+    pub mouse_capture: bool,
+    pub click_areas: ClickAreas,
+    pub user_card: Option<UserCard>,
+    pub filters: Filters,
+    // whispers from hidden users that have no tab open
+    hidden_whispers: Vec<MessageInfo>,
+    // why the last `/filter` was rejected, shown in the filters overlay
+    pub filter_error: Option<String>,
+    pub chatter_panel: ChatterPanel,
+    pub stats: Stats,
+    pub stats_visible: bool,
+    // message the next message sent answers
+    pub reply_to: Option<MessageInfo>,
+    // id of the first message of the conversation shown in the thread view
+    pub thread: Option<String>,
+    // channel and nickname waiting for the ban to be confirmed
+    pub pending_ban: Option<(String, String)>,
+    // what the last moderation action did, shown while moderating
+    pub moderation_status: Option<String>,
+    // selection in the channel picker of virtual tabs
+    pub target_picker: ListState,
+    pub completion: Option<Completion>,
+    // emotes seen in chat this session, for completion
+    pub known_emotes: Vec<String>,
+    pub settings: Settings,
+    pub logger: ChatLogger,
+    // the last log write failed and was reported
+    log_failing: bool,
+    pub store: MessageStore,
+    // the last store write failed and was reported
+    store_failing: bool,
+    // the last history save failed and was reported
+    history_failing: bool,
+    // the last ignores and filters save failed and was reported
+    filters_failing: bool,
+    client: Box<dyn ChatClient>,
+    // whispers can only be sent through the Helix API
+    helix: Option<HelixClient>,
+    events: Option<UnboundedSender<Event>>,
     cancel_token: CancellationToken,
 }
 
 impl App {
-    pub fn new(client: Client, cancel_token: CancellationToken) -> Self {
-        Self {
+    pub fn new(
+        client: impl ChatClient + 'static,
+        settings: Settings,
+        cancel_token: CancellationToken,
+    ) -> AppResult<Self> {
+        let mut pending_notices = Vec::new();
+        // a broken layout shouldn't keep the client from starting
+        let panes = match settings.saved_layout().map(|l| PaneLayout::parse(&l)) {
+            Some(Ok(panes)) => panes,
+            Some(Err(err)) => {
+                pending_notices.push(format!("saved layout ignored: {err}"));
+                PaneLayout::default()
+            }
+            None => PaneLayout::default(),
+        };
+        let focus_on_join =
+            Some(panes.focused_pane().channel.clone()).filter(|c| c.starts_with('#'));
+        let (ignores, rules) = settings.saved_filters();
+        let (filters, filter_errors) = Filters::new(&ignores, &rules, settings.collapse_ignored);
+        pending_notices.extend(
+            filter_errors
+                .into_iter()
+                .map(|err| format!("filter rule ignored: {err}")),
+        );
+        // a typo in [keymap] leaves that key on its default
+        let (keymaps, keymap_errors) = Keymaps::with_overrides(&settings.keymap);
+        pending_notices.extend(
+            keymap_errors
+                .into_iter()
+                .map(|err| format!("keymap override ignored: {err}")),
+        );
+
+        let mut app = Self {
             running: true,
             message_box: MessageBox::default(),
             join_box: JoinBox::default(),
             channels: Vec::new(),
+            closed_channels: Vec::new(),
             current_channel: 0,
             list_state: ListState::default(),
+            panes,
+            focus_on_join,
+            pending_notices,
             app_mode: AppMode::default(),
-            client,
+            search: SearchOverlay::default(),
+            buffer_search: BufferSearch::default(),
+            scroll_to_message: None,
+            focused_message: None,
+            expanded_repeats: Vec::new(),
+            keymaps,
+            help_scroll: 0,
+            mouse_capture: settings.mouse_capture,
+            click_areas: ClickAreas::default(),
+            user_card: None,
+            filters,
+            hidden_whispers: Vec::new(),
+            filter_error: None,
+            chatter_panel: ChatterPanel::default(),
+            stats: Stats::default(),
+            stats_visible: false,
+            reply_to: None,
+            thread: None,
+            pending_ban: None,
+            moderation_status: None,
+            target_picker: ListState::default(),
+            completion: None,
+            known_emotes: Vec::new(),
+            history: InputHistory::load(settings.history_path(), settings.history_size),
+            store: MessageStore::open(&settings.data_dir.join("messages.db"))?,
+            store_failing: false,
+            history_failing: false,
+            filters_failing: false,
+            logger: ChatLogger::new(settings.clone()),
+            log_failing: false,
+            settings,
+            client: Box::new(client),
+            helix: None,
+            events: None,
             cancel_token,
+        };
+        app.open_layout_tabs();
+        Ok(app)
+    }
+
+    // virtual and whisper tabs of a saved layout don't wait for a join
+    fn open_layout_tabs(&mut self) {
+        let names: Vec<String> = self
+            .panes
+            .panes()
+            .iter()
+            .map(|pane| pane.channel.clone())
+            .collect();
+        for name in names {
+            if let Some(nickname) = name.strip_prefix('@') {
+                self.whisper_tab(nickname);
+            } else if let Some(kind) = ChannelKind::from_tab_name(&name) {
+                if !self.channels.iter().any(|c| c.kind == kind) {
+                    self.channels.push(ChannelInfo::virtual_tab(kind));
+                }
+            }
+        }
+        self.load_focused_pane();
+    }
+
+    /// Enables sending whispers, results come back as `Event::Helix` through `events`.
+    pub fn connect_helix(&mut self, helix: HelixClient, events: UnboundedSender<Event>) {
+        self.helix = Some(helix);
+        self.events = Some(events);
+    }
+
+    pub fn send_chat_message(&mut self) -> AppResult<()> {
+        let input = self.message_box.input.text().to_string();
+        // the counter already shows it in red, twitch would just drop it
+        if input.is_empty() || self.message_box.is_too_long() {
+            return Ok(());
+        }
+
+        if input == "/search" || input.starts_with("/search ") {
+            let query = input["/search".len()..].trim().to_string();
+            self.message_box.clear_box();
+            self.search_messages(query);
+            return Ok(());
+        }
+
+        if let Some(channel) = self.channels.get(self.current_channel) {
+            let saved = self.history.push(&channel.name, &input);
+            self.check_history(saved);
+        }
+
+        if input == "/w" || input.starts_with("/w ") {
+            let rest = input["/w".len()..].trim().to_string();
+            self.message_box.clear_box();
+            let (nickname, text) = rest.split_once(' ').unwrap_or((&rest, ""));
+            if nickname.is_empty() {
+                return Ok(());
+            }
+            if text.trim().is_empty() {
+                self.current_channel = self.whisper_tab(nickname);
+                return Ok(());
+            }
+            return self.send_whisper(nickname, text.trim());
+        }
+
+        if input == "/join" || input.starts_with("/join ") {
+            let channel = input["/join".len()..].trim().trim_start_matches('#');
+            // `/join` alone would send `JOIN #`
+            if channel.is_empty() {
+                return Ok(());
+            }
+            let channel = normalize_channel(channel);
+            self.message_box.clear_box();
+            if let Err(err) = self.client.send_join(&channel) {
+                self.notice(format!("{channel} not joined: {err}"));
+            }
+            return Ok(());
+        }
+
+        if input == "/part" || input.starts_with("/part ") {
+            let channel = input["/part".len()..].trim();
+            self.message_box.clear_box();
+            if channel.is_empty() {
+                self.leave_current_channel();
+            } else if let Some(index) = self
+                .channels
+                .iter()
+                .position(|c| c.name.eq_ignore_ascii_case(&normalize_channel(channel)))
+            {
+                self.current_channel = index;
+                self.leave_current_channel();
+            }
+            return Ok(());
+        }
+
+        // `/ignore nick` everywhere, `/ignore nick #channel` in one channel only
+        if let Some(args) = input
+            .strip_prefix("/ignore ")
+            .or_else(|| input.strip_prefix("/unignore "))
+        {
+            let mut words = args.split_whitespace();
+            let ignore = Ignore::new(words.next().unwrap_or_default(), words.next());
+            self.message_box.clear_box();
+            if ignore.nickname.is_empty() {
+                return Ok(());
+            }
+            let changed = if input.starts_with("/ignore ") {
+                self.filters.ignore(ignore)
+            } else {
+                self.filters.unignore(&ignore)
+            };
+            if changed {
+                self.refilter();
+                self.save_filters();
+            }
+            return Ok(());
+        }
+
+        if let Some(rule) = input.strip_prefix("/filter ") {
+            self.message_box.clear_box();
+            match FilterRule::parse(rule) {
+                Ok(rule) => {
+                    self.filters.add_rule(rule);
+                    self.refilter();
+                    self.save_filters();
+                }
+                Err(error) => {
+                    self.filter_error = Some(error.to_string());
+                    self.app_mode = AppMode::Filters;
+                }
+            }
+            return Ok(());
+        }
+
+        if let Some(pattern) = input.strip_prefix("/unfilter ") {
+            self.message_box.clear_box();
+            if self.filters.remove_rule(pattern.trim()) {
+                self.refilter();
+                self.save_filters();
+            }
+            return Ok(());
+        }
+
+        if input == "/filters" {
+            self.message_box.clear_box();
+            self.filter_error = None;
+            self.app_mode = AppMode::Filters;
+            return Ok(());
+        }
+
+        if input == "/target" || input.starts_with("/target ") {
+            let target = input["/target".len()..].trim().to_string();
+            self.message_box.clear_box();
+            if let Some(channel) = self
+                .channels
+                .get_mut(self.current_channel)
+                .filter(|c| c.kind.is_virtual())
+            {
+                if target.is_empty() {
+                    channel.send_target = None;
+                    self.open_target_picker();
+                } else {
+                    channel.send_target = Some(normalize_channel(&target));
+                }
+            }
+            return Ok(());
+        }
+
+        let Some(channel) = self.channels.get(self.current_channel) else {
+            return Ok(());
+        };
+        if channel.kind == ChannelKind::Whisper {
+            let nickname = channel.name[1..].to_string();
+            self.message_box.clear_box();
+            return self.send_whisper(&nickname, &input);
+        }
+        // a reply goes where its parent was said, even from a virtual tab
+        let target = if let Some(parent) = &self.reply_to {
+            parent.channel.clone()
+        } else if channel.kind.is_virtual() {
+            match &channel.send_target {
+                Some(target) => target.clone(),
+                None => {
+                    // the message waits in the input until a channel is picked
+                    self.open_target_picker();
+                    return Ok(());
+                }
+            }
+        } else {
+            channel.name.clone()
+        };
+
+        let command = input.split_whitespace().next().unwrap_or_default();
+        if MODERATION_COMMANDS.contains(&command) {
+            if let Err(err) = self.send_command(&target, &input) {
+                // kept in the input to try again
+                self.notice(format!("{command} not sent: {err}"));
+                return Ok(());
+            }
+            self.message_box.clear_box();
+            return Ok(());
+        }
+
+        let reply_parent = self.reply_to.as_ref().map(|parent| ReplyParent {
+            thread_id: parent
+                .reply_parent
+                .as_ref()
+                .map_or_else(|| parent.id.clone(), |p| p.thread_id.clone()),
+            id: parent.id.clone(),
+            nickname: parent.nickname.clone(),
+            content: parent.content.clone(),
+        });
+        let tags = reply_parent.as_ref().map(|parent| {
+            vec![Tag(
+                "reply-parent-msg-id".to_string(),
+                Some(parent.id.clone()),
+            )]
+        });
+        let sent = self.client.send(Message {
+            tags,
+            prefix: None,
+            command: Command::PRIVMSG(target.clone(), input.clone()),
+        });
+        if let Err(err) = sent {
+            // kept in the input, still replying, to try again
+            self.notice(format!("message not sent: {err}"));
+            return Ok(());
+        }
+        self.reply_to = None;
+        // our own messages are never echoed back by twitch, so log them here
+        let logged = self.logger.log_message(
+            &target,
+            Some(self.client.current_nickname()),
+            &input,
+            &Tags::new(),
+        );
+        self.check_log(logged);
+        // no emote tags on our own messages, words that are emotes seen in chat count as emotes
+        let emotes: Vec<String> = input
+            .split_whitespace()
+            .filter(|word| self.known_emotes.iter().any(|emote| emote == word))
+            .map(str::to_string)
+            .collect();
+        let nickname = self.client.current_nickname().to_string();
+        let now = Local::now();
+        self.stats
+            .channel_mut(&target)
+            .record_message(&nickname, &input, &emotes, now);
+        let message = MessageInfo {
+            id: local_message_id(),
+            channel: target.clone(),
+            user_id: String::new(),
+            nickname: self.client.current_nickname().into(),
+            content: input,
+            timestamp: now,
+            reply_parent,
+            ..Default::default()
+        };
+        self.message_box.clear_box();
+        self.add_chat_message(target, message)
+    }
+
+    /// Sends a twitch command such as `/timeout nick 600` to `channel`.
+    ///
+    /// Commands are not chat, so they are neither logged nor shown.
+    /// Twitch answers with a notice, so a sent command hasn't necessarily worked yet.
+    pub fn send_command(&mut self, channel: &str, command: &str) -> irc::error::Result<()> {
+        self.client.send_privmsg(channel, command)
+    }
+
+    // sends a command from moderation mode and says whether it went out
+    fn send_moderation(&mut self, channel: &str, command: String) {
+        self.moderation_status = Some(match self.send_command(channel, &command) {
+            Ok(()) => format!("sent {command}"),
+            Err(err) => format!("{command} not sent: {err}"),
+        });
+    }
+
+    pub fn add_chat_message(
+        &mut self,
+        target_channel: String,
+        mut chat_message: MessageInfo,
+    ) -> AppResult<()> {
+        // stored as said, so filtered messages can still be searched
+        let stored = self.store.insert(&target_channel, &chat_message);
+        self.check_store(stored);
+        chat_message.filter = self.filters.check(&chat_message, true);
+
+        let own = self.is_own_nickname(&chat_message.nickname);
+        let mention = self.is_mention(&chat_message);
+        let highlight = self.is_highlight(&chat_message);
+        for channel in self.channels.iter_mut() {
+            let shown = match channel.kind {
+                ChannelKind::Channel => channel.name == target_channel,
+                ChannelKind::AllChannels => true,
+                ChannelKind::Mentions => mention,
+                ChannelKind::Highlights => highlight,
+                ChannelKind::FirstChatters => chat_message.arrival == Arrival::FirstMessage,
+                ChannelKind::Whisper => false,
+            };
+            if !shown {
+                continue;
+            }
+            // completing our own name is never useful
+            if !own {
+                channel.saw_chatter(&chat_message.nickname);
+            }
+            channel.push_message(chat_message.clone(), self.settings.collapse_window);
+        }
+        Ok(())
+    }
+
+    // index of the conversation tab with `nickname`, opening it if needed
+    fn whisper_tab(&mut self, nickname: &str) -> usize {
+        let tab = ChannelInfo::whisper(nickname.trim_start_matches('@'));
+        match self.channels.iter().position(|c| c.name == tab.name) {
+            Some(index) => index,
+            None => {
+                self.channels.push(tab);
+                self.channels.len() - 1
+            }
+        }
+    }
+
+    /// Adds a whisper to its conversation tab, counting it as unread if it came from the other side.
+    pub fn add_whisper(&mut self, mut whisper: MessageInfo, incoming: bool) -> AppResult<()> {
+        let stored = self.store.insert(&whisper.channel, &whisper);
+        self.check_store(stored);
+        whisper.filter = self.filters.check(&whisper, true);
+        self.place_whisper(whisper, incoming);
+        Ok(())
+    }
+
+    fn place_whisper(&mut self, whisper: MessageInfo, incoming: bool) {
+        let open = self.channels.iter().position(|c| c.name == whisper.channel);
+        let hidden = whisper.filter == Filtered::Hidden;
+        // kept for when the filter goes away, without opening a tab for it
+        if hidden && open.is_none() {
+            self.hidden_whispers.push(whisper);
+            return;
+        }
+
+        let index = open.unwrap_or_else(|| self.whisper_tab(&whisper.channel));
+        let current = index == self.current_channel;
+        let tab = &mut self.channels[index];
+        if incoming && !current && !hidden {
+            tab.unread += 1;
+        }
+        let at = tab
+            .messages
+            .partition_point(|m| m.timestamp <= whisper.timestamp);
+        tab.messages.insert(at, whisper);
+    }
+
+    // a line from us or twitch rather than a chatter, never stored or logged
+    fn push_notice(&mut self, index: usize, text: String) {
+        let Some(tab) = self.channels.get_mut(index) else {
+            return;
+        };
+        tab.messages.push(MessageInfo {
+            id: local_message_id(),
+            channel: tab.name.clone(),
+            nickname: "*".to_string(),
+            content: text,
+            timestamp: Local::now(),
+            ..Default::default()
+        });
+    }
+
+    /// Shows a notice from the client itself in the current tab.
+    pub fn notice(&mut self, text: String) {
+        if self.channels.is_empty() {
+            self.pending_notices.push(text);
+        } else {
+            self.push_notice(self.current_channel, text);
+        }
+    }
+
+    /// Reports a failed log write once, until writing works again.
+    pub fn check_log(&mut self, result: io::Result<()>) {
+        match result {
+            Ok(()) => self.log_failing = false,
+            Err(err) if !self.log_failing => {
+                self.log_failing = true;
+                self.notice(format!("chat log not written: {err}"));
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Reports a failed message store write once, until writing works again.
+    pub fn check_store(&mut self, result: rusqlite::Result<()>) {
+        match result {
+            Ok(()) => self.store_failing = false,
+            Err(err) if !self.store_failing => {
+                self.store_failing = true;
+                self.notice(format!("message not stored for search: {err}"));
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Reports a failed history save once, until saving works again.
+    pub fn check_history(&mut self, result: io::Result<()>) {
+        match result {
+            Ok(()) => self.history_failing = false,
+            Err(err) if !self.history_failing => {
+                self.history_failing = true;
+                self.notice(format!("sent messages history not saved: {err}"));
+            }
+            Err(_) => {}
         }
     }
 
-    pub fn send_chat_message(&mut self) {
+    /// Shows a twitch notice in its channel, or in the current tab if it isn't for one.
+    pub fn add_notice(&mut self, channel: &str, text: &str) {
+        let index = self
+            .channels
+            .iter()
+            .position(|c| c.name == channel)
+            .unwrap_or(self.current_channel);
+        self.push_notice(index, text.to_string());
+    }
+
+    // shows why a whisper went nowhere in its conversation tab
+    fn whisper_failed(&mut self, nickname: &str, reason: &str) {
+        let index = self.whisper_tab(nickname);
+        self.push_notice(index, format!("whisper not sent: {reason}"));
+    }
+
+    fn send_whisper(&mut self, nickname: &str, text: &str) -> AppResult<()> {
+        let nickname = nickname.trim_start_matches('@').to_lowercase();
+        let own = self.client.current_nickname().to_string();
+        let whisper = MessageInfo {
+            id: local_message_id(),
+            channel: format!("@{nickname}"),
+            user_id: String::new(),
+            nickname: own.clone(),
+            content: text.to_string(),
+            timestamp: Local::now(),
+            ..Default::default()
+        };
+        let logged = self
+            .logger
+            .log_message(&whisper.channel, Some(&own), text, &Tags::new());
+        self.check_log(logged);
+        self.add_whisper(whisper, false)?;
+
+        let (Some(helix), Some(events)) = (self.helix.clone(), self.events.clone()) else {
+            self.whisper_failed(&nickname, "set client_id in [options] to send whispers");
+            return Ok(());
+        };
+        let text = text.to_string();
+        tokio::spawn(async move {
+            let result = helix
+                .send_whisper(&own, &nickname, &text)
+                .await
+                .map_err(|e| e.to_string());
+            events
+                .send(Event::Helix(HelixEvent::WhisperSent(nickname, result)))
+                .unwrap_or_default();
+        });
+        Ok(())
+    }
+
+    pub fn on_helix_event(&mut self, event: HelixEvent) {
+        match event {
+            HelixEvent::WhisperSent(nickname, Err(reason)) => {
+                self.whisper_failed(&nickname, &reason)
+            }
+            HelixEvent::WhisperSent(_, Ok(())) => {}
+        }
+    }
+
+    /// Applies the ignores and filter rules again to every message, after they changed.
+    pub fn refilter(&mut self) {
+        self.filters.reset_counts();
+        for channel in self.channels.iter_mut() {
+            // virtual tabs hold copies of messages already counted in their channel
+            let count = !channel.kind.is_virtual();
+            for message in channel.messages.iter_mut() {
+                message.filter = self.filters.check(message, count);
+            }
+            // a message can't stay folded into one that is hidden now
+            channel.refold(self.settings.collapse_window);
+        }
+        for mut whisper in std::mem::take(&mut self.hidden_whispers) {
+            whisper.filter = self.filters.check(&whisper, true);
+            self.place_whisper(whisper, false);
+        }
+    }
+
+    // written to the data dir so the config file is never rewritten, see `Settings::saved_filters`
+    fn save_filters(&mut self) {
+        let mut saved = String::new();
+        for ignore in &self.filters.ignores {
+            saved.push_str(&format!("ignore {ignore}\n"));
+        }
+        for rule in &self.filters.rules {
+            saved.push_str(&format!("{rule}\n"));
+        }
+        let result = std::fs::create_dir_all(&self.settings.data_dir)
+            .and_then(|_| std::fs::write(self.settings.filters_path(), saved));
+        match result {
+            Ok(()) => self.filters_failing = false,
+            Err(err) if !self.filters_failing => {
+                self.filters_failing = true;
+                self.notice(format!("ignores and filters not saved: {err}"));
+            }
+            Err(_) => {}
+        }
+    }
+
+    pub fn close_filters(&mut self) {
+        self.filter_error = None;
+        self.app_mode = AppMode::Normal;
+    }
+
+    // someone else saying our name as a word, with or without the @
+    fn is_mention(&self, message: &MessageInfo) -> bool {
+        let nickname = self.client.current_nickname();
+        !message.nickname.eq_ignore_ascii_case(nickname)
+            && message
+                .content
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .any(|word| word.eq_ignore_ascii_case(nickname))
+    }
+
+    fn is_highlight(&self, message: &MessageInfo) -> bool {
+        let content = message.content.to_lowercase();
+        self.settings
+            .highlight_words
+            .iter()
+            .any(|word| content.contains(word.as_str()))
+    }
+
+    /// Switches to a virtual tab, opening it with the messages already received.
+    pub fn open_virtual_tab(&mut self, kind: ChannelKind) {
+        if let Some(index) = self.channels.iter().position(|c| c.kind == kind) {
+            self.current_channel = index;
+            return;
+        }
+
+        let mut tab = ChannelInfo::virtual_tab(kind);
+        tab.messages = self
+            .channels
+            .iter()
+            .filter(|c| c.kind == ChannelKind::Channel)
+            .flat_map(|c| c.messages.iter())
+            .filter(|m| match kind {
+                ChannelKind::Mentions => self.is_mention(m),
+                ChannelKind::Highlights => self.is_highlight(m),
+                ChannelKind::FirstChatters => m.arrival == Arrival::FirstMessage,
+                _ => true,
+            })
+            .cloned()
+            .collect();
+        tab.messages.sort_by_key(|m| m.timestamp);
+        self.channels.push(tab);
+        self.current_channel = self.channels.len() - 1;
+    }
+
+    /// Joined channels a virtual tab can send to.
+    pub fn target_candidates(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .filter(|c| c.kind == ChannelKind::Channel)
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
+    fn open_target_picker(&mut self) {
+        if self.target_candidates().is_empty() {
+            return;
+        }
+        self.target_picker = ListState::default().with_selected(Some(0));
+        self.app_mode = AppMode::ChooseTarget;
+    }
+
+    /// Remembers the picked channel for this virtual tab and sends the waiting message.
+    pub fn choose_target(&mut self) -> AppResult<()> {
+        let candidates = self.target_candidates();
+        let picked = self
+            .target_picker
+            .selected()
+            .and_then(|index| candidates.get(index).cloned());
+        self.app_mode = AppMode::Normal;
+        if let Some(channel) = self.channels.get_mut(self.current_channel) {
+            channel.send_target = picked;
+        }
         if self.message_box.input.is_empty() {
+            return Ok(());
+        }
+        self.send_chat_message()
+    }
+
+    pub fn remember_emotes(&mut self, emotes: Vec<String>) {
+        for emote in emotes {
+            if !self.known_emotes.contains(&emote) {
+                self.known_emotes.push(emote);
+            }
+        }
+    }
+
+    /// Completes the word under the cursor, or cycles to the next candidate.
+    pub fn complete(&mut self, reverse: bool) {
+        if let Some(completion) = self.completion.as_mut() {
+            completion.cycle(reverse);
+            let replacement = format!("{} ", completion.current());
+            self.message_box
+                .input
+                .replace_before_cursor(completion.start, &replacement);
             return;
         }
 
-        let current_channel = self.channels.get_mut(self.current_channel);
-        if let Some(channel) = current_channel {
-            self.client
-                .send_privmsg(channel.name.clone(), self.message_box.input.clone())
-                .unwrap();
-            channel.messages.push(MessageInfo {
-                nickname: self.client.current_nickname().into(),
-                content: self.message_box.input.clone(),
-            });
-            self.message_box.clear_box()
+        let input = &self.message_box.input;
+        let start = input.word_start();
+        let before_cursor = input.text_before_cursor();
+        let context = CompletionContext::detect(&before_cursor[..start], &before_cursor[start..]);
+
+        let no_chatters = Vec::new();
+        let chatters = self
+            .channels
+            .get(self.current_channel)
+            .map_or(&no_chatters, |c| &c.recent_chatters);
+        let channels = self.target_candidates();
+        let sources = CompletionSources {
+            chatters,
+            emotes: &self.known_emotes,
+            channels: &channels,
+        };
+
+        let candidates = candidates(&context, &before_cursor[start..], &sources);
+        if candidates.is_empty() {
+            return;
+        }
+        // shift-tab starts from the least likely candidate
+        let index = if reverse { candidates.len() - 1 } else { 0 };
+        let replacement = format!("{} ", candidates[index]);
+        self.message_box
+            .input
+            .replace_before_cursor(start, &replacement);
+        self.completion = Some(Completion {
+            start,
+            candidates,
+            index,
+        });
+    }
+
+    pub fn search_messages(&mut self, query: String) {
+        let parsed = SearchQuery::parse(&query);
+        if parsed.is_empty() {
+            return;
+        }
+
+        match self.store.search(&parsed, SEARCH_LIMIT) {
+            Ok(results) => {
+                self.search.show(query, results);
+                self.app_mode = AppMode::Search;
+            }
+            Err(err) => self.notice(format!("search failed: {err}")),
+        }
+    }
+
+    /// Jumps to the selected search result.
+    ///
+    /// Results still in a channel's scrollback are scrolled into view,
+    /// anything older is shown with its surrounding messages from the store.
+    pub fn open_search_result(&mut self) {
+        let Some(result) = self.search.selected().cloned() else {
+            return;
+        };
+
+        let in_scrollback = self.channels.iter().position(|channel| {
+            channel.name.eq_ignore_ascii_case(&result.channel)
+                && channel.messages.iter().any(|m| m.id == result.message.id)
+        });
+
+        match in_scrollback {
+            Some(index) => {
+                self.current_channel = index;
+                self.scroll_to_message = Some(result.message.id.clone());
+                self.focused_message = Some(result.message.id);
+                self.search.clear();
+                self.app_mode = AppMode::Normal;
+            }
+            None => match self.store.context(&result.message.id, 10) {
+                Ok(context) => {
+                    self.focused_message = Some(result.message.id);
+                    self.search.context = Some(context);
+                }
+                Err(err) => self.notice(format!("search result not opened: {err}")),
+            },
+        }
+    }
+
+    pub fn close_search(&mut self) {
+        if self.search.context.is_some() {
+            self.search.context = None;
+        } else {
+            self.search.clear();
+            self.app_mode = AppMode::Normal;
+        }
+    }
+
+    // ids of messages in the current channel matching the buffer search, newest first
+    fn buffer_search_hits(&self) -> Vec<String> {
+        match self.channels.get(self.current_channel) {
+            Some(channel) => channel
+                .messages
+                .iter()
+                .rev()
+                .filter(|m| self.buffer_search.is_match(m))
+                .map(|m| m.id.clone())
+                .collect(),
+            None => Vec::new(),
         }
     }
 
-    pub fn add_chat_message(&mut self, target_channel: String, chat_message: MessageInfo) {
+    /// Moves to the next buffer search hit, older hits first like reading up the scrollback.
+    pub fn jump_to_match(&mut self, older: bool) {
+        let hits = self.buffer_search_hits();
+        if hits.is_empty() {
+            self.focused_message = None;
+            return;
+        }
+
+        let current = self
+            .focused_message
+            .as_ref()
+            .and_then(|id| hits.iter().position(|hit| hit == id));
+        let next = match (current, older) {
+            (None, true) => 0,
+            (None, false) => hits.len() - 1,
+            (Some(index), true) => (index + 1) % hits.len(),
+            (Some(index), false) => (index + hits.len() - 1) % hits.len(),
+        };
+
+        self.focused_message = Some(hits[next].clone());
+        self.scroll_to_message = Some(hits[next].clone());
+    }
+
+    /// Position of the focused hit and the total hit count, for the search prompt.
+    pub fn buffer_search_position(&self) -> (Option<usize>, usize) {
+        let hits = self.buffer_search_hits();
+        let current = self
+            .focused_message
+            .as_ref()
+            .and_then(|id| hits.iter().position(|hit| hit == id))
+            .map(|index| index + 1);
+        (current, hits.len())
+    }
+
+    // called after every edit of the pattern so matches show up as you type
+    pub fn update_buffer_search(&mut self) {
+        self.focused_message = None;
+        self.jump_to_match(true);
+    }
+
+    pub fn clear_buffer_search(&mut self) {
+        self.buffer_search.clear();
+        self.focused_message = None;
+    }
+
+    /// Recalls an older sent message, from this channel only unless `global` is set.
+    pub fn history_previous(&mut self, global: bool) {
         let channel = self
             .channels
-            .iter_mut()
-            .find(|channel| channel.name == target_channel);
-        // if channel doesn't exist we just die
-        if let Some(channel) = channel {
-            channel.messages.push(chat_message)
+            .get(self.current_channel)
+            .map(|c| c.name.clone());
+        let channel = if global { None } else { channel.as_deref() };
+        if let Some(text) = self
+            .history
+            .previous(channel, self.message_box.input.text())
+        {
+            self.message_box.set_input(text);
         }
     }
 
+    pub fn history_next(&mut self, global: bool) {
+        let channel = self
+            .channels
+            .get(self.current_channel)
+            .map(|c| c.name.clone());
+        let channel = if global { None } else { channel.as_deref() };
+        if let Some(text) = self.history.next(channel) {
+            self.message_box.set_input(text);
+        }
+    }
+
+    pub fn start_history_search(&mut self) {
+        self.history.start_reverse_search();
+        self.message_box.mode = MessageMode::HistorySearch;
+    }
+
+    /// Leaves reverse search, putting the match in the input if `accept` is set.
+    pub fn finish_history_search(&mut self, accept: bool) {
+        if accept {
+            if let Some(found) = self.history.reverse_search_match() {
+                self.message_box.set_input(found.to_string());
+            }
+        }
+        self.message_box.mode = MessageMode::Editing;
+    }
+
     pub fn join_channel(&mut self) {
-        if !self.join_box.channel.starts_with("#") {
-            self.join_box.channel = format!("#{}", self.join_box.channel)
+        // `#` alone would send `JOIN #`
+        if self
+            .join_box
+            .channel
+            .text()
+            .trim_start_matches('#')
+            .is_empty()
+        {
+            return;
         }
 
-        self.client
-            .send_join(self.join_box.channel.clone())
-            .unwrap_or_default();
+        let mut channel = self.join_box.channel.text().to_string();
+        if !channel.starts_with("#") {
+            channel = format!("#{}", channel)
+        }
+
+        self.client.send_join(&channel).unwrap_or_default();
 
         self.join_box.clear_box()
     }
@@ -106,7 +1318,107 @@ impl App {
     pub fn on_join_channel(&mut self, channel: String) {
         if self.channels.iter_mut().any(|c| c.name == channel) {
         } else {
+            if self.focus_on_join.as_ref() == Some(&channel) {
+                self.focus_on_join = None;
+                self.current_channel = self.channels.len();
+            }
             self.channels.push(ChannelInfo::new(channel));
+            for text in std::mem::take(&mut self.pending_notices) {
+                self.push_notice(self.channels.len() - 1, text);
+            }
+        }
+    }
+
+    /// Joins the channels of the saved layout that aren't open yet, once registered with the server.
+    pub fn join_layout_channels(&mut self) {
+        for pane in self.panes.panes() {
+            let channel = &pane.channel;
+            if channel.starts_with('#') && !self.channels.iter().any(|c| &c.name == channel) {
+                self.client.send_join(channel).unwrap_or_default();
+            }
+        }
+    }
+
+    fn current_channel_name(&self) -> String {
+        self.channels
+            .get(self.current_channel)
+            .map(|c| c.name.clone())
+            .unwrap_or_default()
+    }
+
+    // writes the focused pane's channel and scroll position back into the layout
+    fn store_focused_pane(&mut self) {
+        let channel = self.current_channel_name();
+        let pane = self.panes.focused_pane_mut();
+        if !channel.is_empty() {
+            pane.channel = channel;
+        }
+        pane.list_state = self.list_state.clone();
+    }
+
+    fn load_focused_pane(&mut self) {
+        let pane = self.panes.focused_pane();
+        self.list_state = pane.list_state.clone();
+        if let Some(index) = self.channels.iter().position(|c| c.name == pane.channel) {
+            self.current_channel = index;
+        }
+    }
+
+    pub fn focus_pane(&mut self, index: usize) {
+        if index == self.panes.focused || index >= self.panes.count() {
+            return;
+        }
+        self.store_focused_pane();
+        self.panes.focused = index;
+        self.load_focused_pane();
+    }
+
+    pub fn next_pane(&mut self) {
+        self.focus_pane((self.panes.focused + 1) % self.panes.count());
+    }
+
+    pub fn previous_pane(&mut self) {
+        let count = self.panes.count();
+        self.focus_pane((self.panes.focused + count - 1) % count);
+    }
+
+    /// Splits the focused pane, the new pane starts out on the same channel.
+    pub fn split_pane(&mut self, direction: Direction) {
+        self.store_focused_pane();
+        self.panes.split(direction, self.current_channel_name());
+        self.list_state = ListState::default();
+    }
+
+    pub fn close_pane(&mut self) {
+        if self.panes.close_focused() {
+            self.load_focused_pane();
+        }
+    }
+
+    pub fn resize_pane(&mut self, delta: i16) {
+        self.panes.resize_focused(delta);
+    }
+
+    /// Saves the pane layout to the data dir, its channels are joined again on startup.
+    pub fn save_layout(&mut self) {
+        self.store_focused_pane();
+        let path = self.settings.layout_path();
+        let saved = std::fs::create_dir_all(&self.settings.data_dir)
+            .and_then(|_| std::fs::write(&path, self.panes.to_string()));
+        match saved {
+            Ok(()) => self.notice(format!("layout saved to {}", path.display())),
+            Err(err) => self.notice(format!("layout not saved: {err}")),
+        }
+    }
+
+    pub fn previous_channel(&mut self) {
+        if self.channels.is_empty() {
+            return;
+        }
+        if self.current_channel == 0 {
+            self.current_channel = self.channels.len() - 1;
+        } else {
+            self.current_channel -= 1;
         }
     }
 
@@ -121,14 +1433,388 @@ impl App {
         }
     }
 
+    /// Leaves the current channel, asking first if the config wants that.
+    pub fn request_leave_channel(&mut self) {
+        let Some(channel) = self.channels.get(self.current_channel) else {
+            return;
+        };
+        if self.settings.confirm_leave && channel.kind == ChannelKind::Channel {
+            self.app_mode = AppMode::ConfirmLeave;
+        } else {
+            self.leave_current_channel();
+        }
+    }
+
     pub fn leave_current_channel(&mut self) {
         if let Some(channel) = self.channels.get(self.current_channel) {
-            self.client.send_part(channel.name.clone()).unwrap();
-            self.channels.remove(self.current_channel);
+            if channel.kind != ChannelKind::Channel {
+                // nothing to part, and nothing worth reopening
+                self.channels.remove(self.current_channel);
+                self.current_channel = self.current_channel.saturating_sub(1);
+                return;
+            }
+            if let Err(err) = self.client.send_part(&channel.name) {
+                // the tab stays, so leaving can be tried again
+                let text = format!("{} not left: {err}", channel.name);
+                self.notice(text);
+                return;
+            }
+            let channel = self.channels.remove(self.current_channel);
+            self.closed_channels.push(channel);
+            if self.closed_channels.len() > CLOSED_CHANNELS {
+                self.closed_channels.remove(0);
+            }
             self.current_channel = self.current_channel.saturating_sub(1);
         }
     }
 
+    /// Rejoins the most recently left channel with its scrollback intact.
+    pub fn reopen_channel(&mut self) {
+        let Some(channel) = self.closed_channels.pop() else {
+            return;
+        };
+        // joined again since it was closed, its newer tab wins
+        if let Some(index) = self.channels.iter().position(|c| c.name == channel.name) {
+            self.current_channel = index;
+            return;
+        }
+        self.client.send_join(&channel.name).unwrap_or_default();
+        // the JOIN echo finds the channel already open and leaves it be
+        self.channels.push(channel);
+        self.current_channel = self.channels.len() - 1;
+    }
+
+    /// Records someone in a channel, `None` keeps whatever role was seen before.
+    pub fn update_chatter(&mut self, channel: &str, nickname: &str, role: Option<Role>) {
+        let role = if is_broadcaster(channel, nickname) {
+            Some(Role::Broadcaster)
+        } else {
+            role
+        };
+        if let Some(channel) = self.channels.iter_mut().find(|c| c.name == channel) {
+            channel.chatters.add(nickname, role);
+        }
+    }
+
+    /// Records someone who just sent a message, along with their badges and color.
+    pub fn saw_chatter_message(&mut self, channel: &str, nickname: &str, tags: &Tags) {
+        let role = if is_broadcaster(channel, nickname) {
+            Role::Broadcaster
+        } else {
+            Role::from_badges(tags.get("badges").map(String::as_str).unwrap_or_default())
+        };
+        if let Some(channel) = self.channels.iter_mut().find(|c| c.name == channel) {
+            channel.chatters.saw_message(nickname, role, tags);
+        }
+    }
+
+    pub fn remove_chatter(&mut self, channel: &str, nickname: &str) {
+        if let Some(channel) = self.channels.iter_mut().find(|c| c.name == channel) {
+            channel.chatters.remove(nickname);
+        }
+    }
+
+    /// Says the connection was lost, the irc reader is already connecting again.
+    pub fn disconnected(&mut self) {
+        self.notice("disconnected from twitch, reconnecting".to_string());
+    }
+
+    /// Says a reconnect attempt failed and when the next one is.
+    pub fn reconnect_failed(&mut self, error: String, retry_in: Duration) {
+        self.notice(format!(
+            "reconnecting failed: {error}, trying again in {}s",
+            retry_in.as_secs()
+        ));
+    }
+
+    /// Switches to a new connection and joins every open channel on it again.
+    pub fn reconnected(&mut self, client: impl ChatClient + 'static) {
+        self.client = Box::new(client);
+        self.notice("reconnected".to_string());
+        for channel in &self.channels {
+            if channel.kind == ChannelKind::Channel {
+                self.client.send_join(&channel.name).unwrap_or_default();
+            }
+        }
+    }
+
+    pub fn is_own_nickname(&self, nickname: &str) -> bool {
+        self.client
+            .current_nickname()
+            .eq_ignore_ascii_case(nickname)
+    }
+
+    /// Shows and focuses the chatter panel, or hides it if it is already shown.
+    pub fn toggle_chatter_panel(&mut self) {
+        if self.chatter_panel.visible {
+            self.chatter_panel.visible = false;
+        } else {
+            self.chatter_panel.visible = true;
+            self.app_mode = AppMode::Chatters;
+        }
+    }
+
+    /// Chatters of the current channel matching the panel's filter.
+    pub fn visible_chatters(&self) -> Vec<(Role, String)> {
+        self.channels
+            .get(self.current_channel)
+            .map(|c| c.chatters.sorted(&self.chatter_panel.filter))
+            .unwrap_or_default()
+    }
+
+    pub fn open_selected_chatter(&mut self) {
+        let chatters = self.visible_chatters();
+        if let Some((_, nickname)) = chatters.get(self.chatter_panel.selected) {
+            self.open_user_card(nickname.clone());
+        }
+    }
+
+    pub fn open_user_card(&mut self, nickname: String) {
+        let channel = self.current_channel_name();
+        self.user_card = Some(UserCard {
+            nickname,
+            channel,
+            stored: None,
+            error: None,
+            scroll: 0,
+            from_chatters: matches!(self.app_mode, AppMode::Chatters),
+        });
+        self.app_mode = AppMode::UserCard;
+    }
+
+    pub fn close_user_card(&mut self) {
+        let from_chatters = self.user_card.take().is_some_and(|card| card.from_chatters);
+        self.app_mode = if from_chatters {
+            AppMode::Chatters
+        } else {
+            AppMode::Normal
+        };
+    }
+
+    /// Switches the user card between this session's messages and everything stored.
+    pub fn toggle_user_card_history(&mut self) {
+        let Some(card) = self.user_card.as_mut() else {
+            return;
+        };
+        card.error = None;
+        if card.stored.take().is_none() {
+            let query = SearchQuery {
+                from: Some(card.nickname.clone()),
+                ..Default::default()
+            };
+            match self.store.search(&query, USER_CARD_HISTORY) {
+                Ok(mut stored) => {
+                    // oldest first like the session messages
+                    stored.reverse();
+                    card.stored = Some(stored);
+                }
+                Err(err) => card.error = Some(format!("stored messages not loaded: {err}")),
+            }
+        }
+        card.scroll = 0;
+    }
+
+    /// Closes the user card and puts a command for its user in the input to confirm with enter.
+    pub fn user_card_command(&mut self, command: UserCardCommand) {
+        let Some(card) = self.user_card.take() else {
+            return;
+        };
+        let nickname = card.nickname;
+        let input = match command {
+            UserCardCommand::Mention => format!("@{nickname} "),
+            UserCardCommand::Whisper => format!("/w {nickname} "),
+            UserCardCommand::Ignore => format!("/ignore {nickname}"),
+            UserCardCommand::Timeout => format!("/timeout {nickname} {DEFAULT_TIMEOUT}"),
+            UserCardCommand::Ban => format!("/ban {nickname} "),
+        };
+        self.message_box.set_input(input);
+        self.message_box.mode = MessageMode::Editing;
+        self.app_mode = AppMode::Normal;
+    }
+
+    pub fn start_moderating(&mut self) {
+        let newest = self
+            .channels
+            .get(self.current_channel)
+            .and_then(|c| c.messages.iter().rev().find(|m| self.is_drawn(m)))
+            .map(|m| m.id.clone());
+        self.focused_message = newest;
+        self.moderation_status = None;
+        self.app_mode = AppMode::Moderate;
+    }
+
+    pub fn stop_moderating(&mut self) {
+        self.focused_message = None;
+        self.app_mode = AppMode::Normal;
+    }
+
+    /// Moves the moderation cursor one message newer or older.
+    pub fn select_message(&mut self, newer: bool) {
+        let Some(channel) = self.channels.get(self.current_channel) else {
+            return;
+        };
+        // hidden and folded messages can't be selected
+        let messages: Vec<&MessageInfo> = channel
+            .messages
+            .iter()
+            .filter(|m| self.is_drawn(m))
+            .collect();
+        let current = self
+            .focused_message
+            .as_ref()
+            .and_then(|id| messages.iter().position(|m| &m.id == id));
+        let next = match current {
+            Some(index) if newer => (index + 1).min(messages.len().saturating_sub(1)),
+            Some(index) => index.saturating_sub(1),
+            None => messages.len().saturating_sub(1),
+        };
+        if let Some(message) = messages.get(next) {
+            self.focused_message = Some(message.id.clone());
+            self.scroll_to_message = Some(message.id.clone());
+        }
+    }
+
+    /// Whether a message gets a line of its own in the messages list.
+    pub fn is_drawn(&self, message: &MessageInfo) -> bool {
+        message.filter != Filtered::Hidden
+            && message
+                .repeat_of
+                .as_ref()
+                .is_none_or(|head| self.expanded_repeats.contains(head))
+    }
+
+    /// Expands the repeats folded into the selected message, or folds them back.
+    pub fn toggle_selected_repeats(&mut self) {
+        let Some(message) = self.selected_message() else {
+            return;
+        };
+        let head = message
+            .repeat_of
+            .clone()
+            .unwrap_or_else(|| message.id.clone());
+        if message.repeat_of.is_none() && message.repeats == 0 {
+            return;
+        }
+        if self.expanded_repeats.contains(&head) {
+            self.expanded_repeats.retain(|id| *id != head);
+            // the selected repeat is folded away, select what it was folded into
+            self.focused_message = Some(head);
+        } else {
+            self.expanded_repeats.push(head);
+        }
+    }
+
+    fn selected_message(&self) -> Option<&MessageInfo> {
+        let id = self.focused_message.as_ref()?;
+        self.channels
+            .get(self.current_channel)?
+            .messages
+            .iter()
+            .find(|m| &m.id == id)
+    }
+
+    pub fn delete_selected(&mut self) {
+        let Some(message) = self.selected_message().cloned() else {
+            return;
+        };
+        // messages we sent ourselves never got an id from twitch
+        if message.id.starts_with("local-") {
+            self.moderation_status = Some("only messages from twitch can be deleted".into());
+            return;
+        }
+        self.send_moderation(&message.channel, format!("/delete {}", message.id));
+    }
+
+    pub fn timeout_selected(&mut self, seconds: u32) {
+        let Some(message) = self.selected_message().cloned() else {
+            return;
+        };
+        self.send_moderation(
+            &message.channel,
+            format!("/timeout {} {seconds}", message.nickname),
+        );
+    }
+
+    pub fn request_ban_selected(&mut self) {
+        if let Some(message) = self.selected_message() {
+            self.pending_ban = Some((message.channel.clone(), message.nickname.clone()));
+            self.app_mode = AppMode::ConfirmBan;
+        }
+    }
+
+    /// Bans the pending user if `confirmed`, either way going back to moderating.
+    pub fn finish_ban(&mut self, confirmed: bool) {
+        if let Some((channel, nickname)) = self.pending_ban.take() {
+            if confirmed {
+                self.send_moderation(&channel, format!("/ban {nickname}"));
+            }
+        }
+        self.app_mode = AppMode::Moderate;
+    }
+
+    /// Starts writing a reply to the selected message.
+    pub fn reply_to_selected(&mut self) {
+        let Some(message) = self.selected_message().cloned() else {
+            return;
+        };
+        if message.id.starts_with("local-") {
+            self.moderation_status = Some("only messages from twitch can be replied to".into());
+            return;
+        }
+        self.reply_to = Some(message);
+        self.focused_message = None;
+        self.app_mode = AppMode::Normal;
+        self.message_box.mode = MessageMode::Editing;
+    }
+
+    pub fn cancel_reply(&mut self) {
+        self.reply_to = None;
+    }
+
+    pub fn show_selected_thread(&mut self) {
+        if let Some(message) = self.selected_message() {
+            let root = message
+                .reply_parent
+                .as_ref()
+                .map_or_else(|| message.id.clone(), |p| p.thread_id.clone());
+            self.thread = Some(root);
+            self.app_mode = AppMode::Thread;
+        }
+    }
+
+    pub fn close_thread(&mut self) {
+        self.thread = None;
+        self.app_mode = AppMode::Moderate;
+    }
+
+    /// Every message of the conversation in the thread view, oldest first.
+    pub fn thread_messages(&self) -> Vec<&MessageInfo> {
+        let Some(root) = &self.thread else {
+            return Vec::new();
+        };
+        self.channels
+            .iter()
+            .filter(|c| c.kind == ChannelKind::Channel)
+            .flat_map(|c| c.messages.iter())
+            .filter(|m| {
+                &m.id == root
+                    || m.reply_parent
+                        .as_ref()
+                        .is_some_and(|p| &p.thread_id == root)
+            })
+            .collect()
+    }
+
+    pub fn copy_selected_name(&mut self) {
+        if let Some(message) = self.selected_message() {
+            let nickname = &message.nickname;
+            self.moderation_status = Some(match clipboard::copy(nickname) {
+                Ok(()) => format!("copied {nickname}"),
+                Err(err) => format!("{nickname} not copied: {err}"),
+            });
+        }
+    }
+
     pub fn quit(&mut self) {
         self.cancel_token.cancel();
         self.running = false;
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local};
+use ratatui::widgets::ListState;
+
+use crate::{store::StoredMessage, twitch::client_stream::Tags};
+
+/// Where a chatter sits in the chatter list.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Role {
+    Broadcaster,
+    Moderator,
+    Vip,
+    Viewer,
+}
+
+impl Role {
+    /// Highest role in a twitch `badges` tag such as `moderator/1,subscriber/12`.
+    pub fn from_badges(badges: &str) -> Self {
+        badges
+            .split(',')
+            .filter_map(|badge| match badge.split('/').next()? {
+                "broadcaster" => Some(Role::Broadcaster),
+                "moderator" => Some(Role::Moderator),
+                "vip" => Some(Role::Vip),
+                _ => None,
+            })
+            .min()
+            .unwrap_or(Role::Viewer)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Broadcaster => "broadcaster",
+            Role::Moderator => "moderator",
+            Role::Vip => "vip",
+            Role::Viewer => "viewer",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Role::Broadcaster => "broadcaster",
+            Role::Moderator => "moderators",
+            Role::Vip => "vips",
+            Role::Viewer => "viewers",
+        }
+    }
+}
+
+/// What we know about someone in a channel.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Chatter {
+    // display name as last seen
+    pub nickname: String,
+    pub role: Role,
+    // badge names such as `subscriber/12`, empty until they chat
+    pub badges: Vec<String>,
+    // `#RRGGBB` name color they picked, if any
+    pub color: Option<String>,
+    pub first_seen: DateTime<Local>,
+}
+
+/// Users known to be in a channel, from membership events and chat.
+#[derive(Default, PartialEq, Eq)]
+pub struct ChatterList {
+    // keyed by lowercase login
+    members: HashMap<String, Chatter>,
+}
+
+impl ChatterList {
+    fn entry(&mut self, nickname: &str) -> &mut Chatter {
+        let chatter = self
+            .members
+            .entry(nickname.to_lowercase())
+            .or_insert_with(|| Chatter {
+                nickname: nickname.to_string(),
+                role: Role::Viewer,
+                badges: Vec::new(),
+                color: None,
+                first_seen: Local::now(),
+            });
+        chatter.nickname = nickname.to_string();
+        chatter
+    }
+
+    /// Adds a chatter, keeping the role already known when `role` is `None`.
+    pub fn add(&mut self, nickname: &str, role: Option<Role>) {
+        let chatter = self.entry(nickname);
+        if let Some(role) = role {
+            chatter.role = role;
+        }
+    }
+
+    /// Updates a chatter from the tags of a message they sent.
+    pub fn saw_message(&mut self, nickname: &str, role: Role, tags: &Tags) {
+        let nickname = tags
+            .get("display-name")
+            .filter(|name| name.eq_ignore_ascii_case(nickname))
+            .map_or(nickname, String::as_str);
+        let chatter = self.entry(nickname);
+        chatter.role = role;
+        chatter.badges = tags
+            .get("badges")
+            .map(|badges| {
+                badges
+                    .split(',')
+                    .filter(|b| !b.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        chatter.color = tags.get("color").filter(|c| !c.is_empty()).cloned();
+    }
+
+    pub fn remove(&mut self, nickname: &str) {
+        self.members.remove(&nickname.to_lowercase());
+    }
+
+    pub fn get(&self, nickname: &str) -> Option<&Chatter> {
+        self.members.get(&nickname.to_lowercase())
+    }
+
+    pub fn count(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Chatters whose name contains `filter`, ordered by role then name.
+    pub fn sorted(&self, filter: &str) -> Vec<(Role, String)> {
+        let filter = filter.to_lowercase();
+        let mut chatters: Vec<(Role, String)> = self
+            .members
+            .iter()
+            .filter(|(login, _)| login.contains(&filter))
+            .map(|(_, chatter)| (chatter.role, chatter.nickname.clone()))
+            .collect();
+        chatters.sort_by_key(|(role, nickname)| (*role, nickname.to_lowercase()));
+        chatters
+    }
+}
+
+/// The user card popup for one chatter.
+pub struct UserCard {
+    pub nickname: String,
+    pub channel: String,
+    // their messages in every channel from the store, shown instead of this session's
+    pub stored: Option<Vec<StoredMessage>>,
+    // why the stored messages couldn't be loaded
+    pub error: Option<String>,
+    // messages scrolled up from the newest
+    pub scroll: usize,
+    // opened from the chatter panel, which gets focus back on close
+    pub from_chatters: bool,
+}
+
+/// The toggleable side panel listing the current channel's chatters.
+#[derive(Default)]
+pub struct ChatterPanel {
+    pub visible: bool,
+    pub filter: String,
+    // index into the filtered chatters, headers not counted
+    pub selected: usize,
+    pub list_state: ListState,
+}
+
+impl ChatterPanel {
+    pub fn enter_char(&mut self, new_char: char) {
+        self.filter.push(new_char);
+        self.selected = 0;
+    }
+
+    pub fn delete_char(&mut self) {
+        self.filter.pop();
+        self.selected = 0;
+    }
+
+    pub fn select_next(&mut self, count: usize) {
+        if self.selected + 1 < count {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_highest_badge_decides_the_role() {
+        for (badges, role) in [
+            (
+                "broadcaster/1,moderator/1,vip/1,subscriber/12",
+                Role::Broadcaster,
+            ),
+            (
+                "subscriber/12,vip/1,moderator/1,broadcaster/1",
+                Role::Broadcaster,
+            ),
+            ("subscriber/3,moderator/1,vip/1", Role::Moderator),
+            ("vip/1,moderator/1", Role::Moderator),
+            ("subscriber/24,vip/1", Role::Vip),
+            ("vip/1,premium/1", Role::Vip),
+            // subscribers are listed with the viewers
+            ("subscriber/6,sub-gifter/5", Role::Viewer),
+            ("premium/1", Role::Viewer),
+            ("", Role::Viewer),
+        ] {
+            assert_eq!(Role::from_badges(badges), role, "{badges}");
+        }
+    }
+}
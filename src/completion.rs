@@ -0,0 +1,178 @@
+/// Slash commands understood by the chat input.
+pub const SLASH_COMMANDS: &[&str] = &[
+    "/filter",
+    "/filters",
+    "/ignore",
+    "/join",
+    "/part",
+    "/search",
+    "/target",
+    "/unfilter",
+    "/unignore",
+    "/w",
+];
+
+/// Twitch moderation commands, sent to the channel rather than shown as chat.
+pub const MODERATION_COMMANDS: &[&str] = &["/ban", "/delete", "/timeout", "/unban"];
+
+/// What is being completed, decided by the word under the cursor.
+#[derive(PartialEq, Eq, Debug)]
+pub enum CompletionContext {
+    // `@ni`
+    Mention,
+    // `/se` at the start of the input
+    Command,
+    // `#ch` after `/join ` or `/part `
+    Channel,
+    // anything else, nicknames and emotes
+    Word,
+}
+
+impl CompletionContext {
+    pub fn detect(input_before_word: &str, word: &str) -> Self {
+        if word.starts_with('@') {
+            CompletionContext::Mention
+        } else if word.starts_with('/') && input_before_word.trim().is_empty() {
+            CompletionContext::Command
+        } else if matches!(input_before_word.trim(), "/join" | "/part") {
+            CompletionContext::Channel
+        } else {
+            CompletionContext::Word
+        }
+    }
+}
+
+/// Sources of completions, nicknames are expected most recently active first.
+pub struct CompletionSources<'a> {
+    pub chatters: &'a [String],
+    pub emotes: &'a [String],
+    pub channels: &'a [String],
+}
+
+/// Candidates for `word` in order of preference.
+pub fn candidates(
+    context: &CompletionContext,
+    word: &str,
+    sources: &CompletionSources,
+) -> Vec<String> {
+    let matches = |candidate: &str, prefix: &str| {
+        candidate.to_lowercase().starts_with(&prefix.to_lowercase())
+    };
+
+    match context {
+        CompletionContext::Mention => {
+            let prefix = word.trim_start_matches('@');
+            sources
+                .chatters
+                .iter()
+                .filter(|nick| matches(nick, prefix))
+                .map(|nick| format!("@{nick}"))
+                .collect()
+        }
+        CompletionContext::Command => SLASH_COMMANDS
+            .iter()
+            .chain(MODERATION_COMMANDS)
+            .filter(|command| matches(command, word))
+            .map(|command| command.to_string())
+            .collect(),
+        CompletionContext::Channel => {
+            let prefix = word.trim_start_matches('#');
+            sources
+                .channels
+                .iter()
+                .filter(|channel| matches(channel.trim_start_matches('#'), prefix))
+                .cloned()
+                .collect()
+        }
+        CompletionContext::Word => {
+            let mut found: Vec<String> = Vec::new();
+            for candidate in sources.chatters.iter().chain(sources.emotes.iter()) {
+                if matches(candidate, word) && !found.contains(candidate) {
+                    found.push(candidate.clone());
+                }
+            }
+            found
+        }
+    }
+}
+
+/// An in progress tab completion, cycled through by pressing tab again.
+pub struct Completion {
+    // byte index where the completed word starts
+    pub start: usize,
+    pub candidates: Vec<String>,
+    pub index: usize,
+}
+
+impl Completion {
+    pub fn current(&self) -> &str {
+        &self.candidates[self.index]
+    }
+
+    pub fn cycle(&mut self, reverse: bool) {
+        let len = self.candidates.len();
+        self.index = if reverse {
+            (self.index + len - 1) % len
+        } else {
+            (self.index + 1) % len
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn context_comes_from_the_word_and_what_is_before_it() {
+        use CompletionContext::*;
+        assert_eq!(CompletionContext::detect("", "@vi"), Mention);
+        assert_eq!(CompletionContext::detect("hi ", "@vi"), Mention);
+        assert_eq!(CompletionContext::detect("", "/se"), Command);
+        // a slash later in the message is just a word
+        assert_eq!(CompletionContext::detect("see ", "/se"), Word);
+        assert_eq!(CompletionContext::detect("/join ", "#ch"), Channel);
+        assert_eq!(CompletionContext::detect("/part ", "ch"), Channel);
+        assert_eq!(CompletionContext::detect("/w ", "vi"), Word);
+        assert_eq!(CompletionContext::detect("", "Kap"), Word);
+    }
+
+    #[test]
+    fn candidates_keep_the_order_of_their_sources() {
+        let chatters = strings(&["Viewer", "visitor", "other"]);
+        let emotes = strings(&["VirtualHug", "Kappa", "visitor"]);
+        let channels = strings(&["#chan", "#cheese", "#other"]);
+        let sources = CompletionSources {
+            chatters: &chatters,
+            emotes: &emotes,
+            channels: &channels,
+        };
+
+        assert_eq!(
+            candidates(&CompletionContext::Mention, "@vi", &sources),
+            ["@Viewer", "@visitor"]
+        );
+        // chatters first, and a name that is both is offered once
+        assert_eq!(
+            candidates(&CompletionContext::Word, "vi", &sources),
+            ["Viewer", "visitor", "VirtualHug"]
+        );
+        assert_eq!(
+            candidates(&CompletionContext::Channel, "#ch", &sources),
+            ["#chan", "#cheese"]
+        );
+        assert_eq!(
+            candidates(&CompletionContext::Channel, "oth", &sources),
+            ["#other"]
+        );
+        assert_eq!(
+            candidates(&CompletionContext::Command, "/un", &sources),
+            ["/unfilter", "/unignore", "/unban"]
+        );
+        assert!(candidates(&CompletionContext::Word, "zz", &sources).is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::App;
+
+pub fn render_buffer_search(app: &App, area: Rect, frame: &mut Frame) {
+    use Constraint::Length;
+
+    let layout = Layout::vertical([Length(1), Length(3)]);
+    let [help_area, prompt_area] = layout.areas(area);
+
+    let search = &app.buffer_search;
+    let toggle = |name: &'static str, on: bool| {
+        let style = if on {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().add_modifier(Modifier::DIM)
+        };
+        Span::styled(format!("[{name}]"), style)
+    };
+    let help_message = Paragraph::new(Line::from(vec![
+        "<enter> keep, <ESC> cancel, <ctrl + r> ".into(),
+        toggle("regex", search.use_regex),
+        " <ctrl + t> ".into(),
+        toggle("ignore case", search.case_insensitive),
+    ]));
+
+    let status = match (&search.error, app.buffer_search_position()) {
+        (Some(_), _) => "invalid pattern".to_string(),
+        (None, (_, 0)) if !search.pattern.is_empty() => "no matches".to_string(),
+        (None, (Some(current), total)) => format!("{current}/{total}"),
+        _ => String::new(),
+    };
+    let prompt = Paragraph::new(format!("/{}", search.pattern))
+        .style(Style::default().fg(Color::Yellow))
+        .block(Block::bordered().title("Search").title_bottom(status));
+
+    frame.set_cursor_position((
+        prompt_area.x + search.pattern.width() as u16 + 2,
+        prompt_area.y + 1,
+    ));
+    frame.render_widget(help_message, help_area);
+    frame.render_widget(prompt, prompt_area);
+}
@@ -0,0 +1,60 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, List, ListItem},
+    Frame,
+};
+
+use crate::app::{App, AppMode};
+
+pub fn render_chatters(app: &mut App, area: Rect, frame: &mut Frame) {
+    let chatters = app.visible_chatters();
+    let total = app
+        .channels
+        .get(app.current_channel)
+        .map_or(0, |c| c.chatters.count());
+
+    // a header starts every role group, the selection skips over them
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut selected_item = None;
+    let mut last_role = None;
+    for (index, (role, nickname)) in chatters.iter().enumerate() {
+        if last_role != Some(*role) {
+            let group_size = chatters.iter().filter(|(r, _)| r == role).count();
+            items.push(ListItem::new(Line::styled(
+                format!("{} ({group_size})", role.title()),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            last_role = Some(*role);
+        }
+        if index == app.chatter_panel.selected {
+            selected_item = Some(items.len());
+        }
+        items.push(ListItem::new(format!(" {nickname}")));
+    }
+
+    let focused = matches!(app.app_mode, AppMode::Chatters);
+    let mut block = Block::bordered().title(format!("chatters ({total})"));
+    if !app.chatter_panel.filter.is_empty() {
+        block = block.title_bottom(format!("filter: {}", app.chatter_panel.filter));
+    } else if focused {
+        block = block.title_bottom("type to filter");
+    }
+    if focused {
+        block = block.border_style(Style::default().fg(Color::Cyan));
+    }
+
+    let mut highlight_style = Style::default();
+    if focused {
+        highlight_style = highlight_style.add_modifier(Modifier::REVERSED);
+    }
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(highlight_style);
+
+    app.chatter_panel.list_state.select(selected_item);
+    frame.render_stateful_widget(list, area, &mut app.chatter_panel.list_state);
+}
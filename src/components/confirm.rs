@@ -0,0 +1,57 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    text::{Line, Text},
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{app::App, keymap::Action};
+
+pub fn render_confirm_leave(app: &App, area: Rect, frame: &mut Frame) {
+    let channel = app
+        .channels
+        .get(app.current_channel)
+        .map(|c| c.name.as_str())
+        .unwrap_or_default();
+    let text = Text::from(vec![
+        Line::from(format!("Leave {channel}?")),
+        Line::styled(
+            format!(
+                "<{}> leave, <{}> stay",
+                app.keymaps.confirm.hint(Action::Confirm),
+                app.keymaps.confirm.hint(Action::Cancel)
+            ),
+            Style::default().fg(Color::Yellow),
+        ),
+    ]);
+    let dialog = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .block(Block::bordered().title("Leave channel"));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(dialog, area);
+}
+
+pub fn render_confirm_ban(app: &App, area: Rect, frame: &mut Frame) {
+    let Some((channel, nickname)) = &app.pending_ban else {
+        return;
+    };
+    let text = Text::from(vec![
+        Line::from(format!("Ban {nickname} from {channel}?")),
+        Line::styled(
+            format!(
+                "<{}> ban, <{}> cancel",
+                app.keymaps.confirm.hint(Action::Confirm),
+                app.keymaps.confirm.hint(Action::Cancel)
+            ),
+            Style::default().fg(Color::Yellow),
+        ),
+    ]);
+    let dialog = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .block(Block::bordered().title("Ban user"));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(dialog, area);
+}
@@ -0,0 +1,67 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{app::App, keymap::Action};
+
+fn heading(title: &'static str) -> Line<'static> {
+    Line::styled(title, Style::default().add_modifier(Modifier::BOLD))
+}
+
+pub fn render_filters(app: &App, area: Rect, frame: &mut Frame) {
+    let filters = &app.filters;
+    let mut lines = Vec::new();
+    if let Some(error) = &app.filter_error {
+        lines.push(Line::styled(error.clone(), Style::default().fg(Color::Red)));
+        lines.push(Line::default());
+    }
+
+    let mode = if filters.collapse_ignored {
+        "collapsed"
+    } else {
+        "hidden"
+    };
+    lines.push(heading("ignored users"));
+    if filters.ignores.is_empty() {
+        lines.push(Line::from("none, add with /ignore nick [#channel]"));
+    }
+    lines.extend(filters.ignores.iter().map(|ignore| {
+        Line::from(match &ignore.channel {
+            Some(channel) => format!("{} in {channel}", ignore.nickname),
+            None => format!("{} everywhere", ignore.nickname),
+        })
+    }));
+    lines.push(Line::styled(
+        format!("{} messages {mode}", filters.ignored_hits),
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+    lines.push(Line::default());
+
+    lines.push(heading("filter rules"));
+    if filters.rules.is_empty() {
+        lines.push(Line::from(
+            "none, add with /filter hide|dim <pattern> or /filter replace <pattern> => <text>",
+        ));
+    }
+    lines.extend(filters.rules.iter().map(|rule| {
+        Line::from(vec![
+            Span::styled(
+                format!("{:>6} ", rule.hits),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw(rule.to_string()),
+        ])
+    }));
+
+    let paragraph =
+        Paragraph::new(lines).block(Block::bordered().title("filters").title_bottom(format!(
+            "/unignore and /unfilter remove entries, <{}> to close",
+            app.keymaps.filters.hint(Action::Cancel)
+        )));
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,124 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Clear, Row, Table, TableState},
+    Frame,
+};
+
+use crate::{
+    app::App,
+    keymap::{Action, Keymap},
+};
+
+// text prompts take every other key as input, so their keys here can't be rebound
+const EDITING_KEYS: &[(&str, &str)] = &[
+    ("enter", "send the message"),
+    ("esc", "stop writing"),
+    (
+        "tab, shift-tab",
+        "complete nicknames, emotes, commands and channels",
+    ),
+    ("up, down", "recall messages sent in this channel"),
+    (
+        "shift-up, shift-down",
+        "recall messages sent in any channel",
+    ),
+    ("ctrl-r", "search sent messages"),
+    ("ctrl-a, ctrl-e", "go to the start or end"),
+    ("alt-b, alt-f", "go a word back or forward"),
+    ("ctrl-w, alt-d", "cut the word before or after the cursor"),
+    ("ctrl-u, ctrl-k", "cut to the start or end"),
+    (
+        "ctrl-y, alt-y",
+        "paste what was cut, then cycle through older cuts",
+    ),
+    ("ctrl-z, alt-z", "undo, redo"),
+];
+
+const HISTORY_SEARCH_KEYS: &[(&str, &str)] = &[
+    ("ctrl-r", "find an older match"),
+    ("enter", "put the match in the input"),
+    ("esc", "go back to what was typed"),
+];
+
+const JOIN_KEYS: &[(&str, &str)] = &[("enter", "join the channel"), ("esc", "close")];
+
+const BUFFER_SEARCH_KEYS: &[(&str, &str)] = &[
+    ("enter", "keep the matches highlighted"),
+    ("esc", "clear the search"),
+    ("ctrl-r", "toggle regex"),
+    ("ctrl-t", "toggle case sensitivity"),
+];
+
+fn fixed_rows(title: &'static str, keys: &[(&str, &'static str)]) -> Vec<Row<'static>> {
+    let mut rows = vec![heading(title)];
+    rows.extend(
+        keys.iter()
+            .map(|(keys, description)| binding_row(keys.to_string(), description)),
+    );
+    rows.push(Row::new(vec![Line::default()]));
+    rows
+}
+
+fn heading(title: &'static str) -> Row<'static> {
+    Row::new(vec![Line::styled(
+        title,
+        Style::default().add_modifier(Modifier::BOLD),
+    )])
+}
+
+fn binding_row(keys: String, description: &'static str) -> Row<'static> {
+    Row::new(vec![
+        Line::styled(keys, Style::default().fg(Color::Yellow)),
+        Line::from(description),
+    ])
+}
+
+fn binding_rows(title: &'static str, keymap: &Keymap) -> Vec<Row<'static>> {
+    let mut rows = vec![heading(title)];
+    rows.extend(
+        keymap
+            .describe()
+            .into_iter()
+            .map(|(keys, action)| binding_row(keys, action.description())),
+    );
+    rows.push(Row::new(vec![Line::default()]));
+    rows
+}
+
+pub fn render_help(app: &mut App, area: Rect, frame: &mut Frame) {
+    let keymaps = &app.keymaps;
+    let mut rows = binding_rows("chat", &keymaps.normal);
+    rows.extend(fixed_rows("writing", EDITING_KEYS));
+    rows.extend(fixed_rows("searching sent messages", HISTORY_SEARCH_KEYS));
+    rows.extend(fixed_rows("join dialog", JOIN_KEYS));
+    rows.extend(fixed_rows("searching this channel", BUFFER_SEARCH_KEYS));
+    rows.extend(binding_rows("search results", &keymaps.search));
+    rows.extend(binding_rows("moderation", &keymaps.moderate));
+    rows.extend(binding_rows("chatter list", &keymaps.chatters));
+    rows.extend(binding_rows("user card", &keymaps.user_card));
+    rows.extend(binding_rows("leave and ban questions", &keymaps.confirm));
+    rows.extend(binding_rows("thread", &keymaps.thread));
+    rows.extend(binding_rows("filters", &keymaps.filters));
+    rows.extend(binding_rows("send target picker", &keymaps.target));
+
+    // the last rows stop at the bottom instead of scrolling out of view
+    let visible = area.height.saturating_sub(2);
+    let max_scroll = (rows.len() as u16).saturating_sub(visible);
+    app.help_scroll = app.help_scroll.min(max_scroll);
+    let mut state = TableState::default().with_offset(app.help_scroll as usize);
+
+    let table = Table::new(rows, [Constraint::Length(24), Constraint::Min(10)]).block(
+        Block::bordered()
+            .title("key bindings")
+            .title_bottom(format!(
+                "<{}>/<{}> to scroll, any other key to close",
+                keymaps.help.hint(Action::ScrollDown),
+                keymaps.help.hint(Action::ScrollUp)
+            )),
+    );
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut state);
+}
@@ -6,34 +6,160 @@ use ratatui::{
     Frame,
 };
 
-use crate::{app::App, messagebox::MessageMode};
+use crate::{
+    app::{App, AppMode, ChannelKind},
+    keymap::Action,
+    messagebox::{MessageMode, MAX_MESSAGE_LENGTH},
+};
+
+// rows of text the input needs at this width, capped by the config
+fn input_rows(app: &App, width: u16) -> u16 {
+    let (rows, _) = app.message_box.input.wrap(width.saturating_sub(2) as usize);
+    (rows.len() as u16).clamp(1, app.settings.input_max_lines)
+}
+
+// replies and virtual tabs say where the message is going
+fn input_title(app: &App) -> String {
+    if let Some(parent) = &app.reply_to {
+        return format!("Reply to @{} in {}", parent.nickname, parent.channel);
+    }
+    match app.channels.get(app.current_channel) {
+        Some(channel) if channel.kind == ChannelKind::Whisper => {
+            format!("Whisper to {}", &channel.name[1..])
+        }
+        Some(channel) if channel.kind.is_virtual() => match &channel.send_target {
+            Some(target) => format!("Input to {target}"),
+            None => "Input, pick a channel on send".to_string(),
+        },
+        _ => "Input".to_string(),
+    }
+}
+
+/// Height of the whole input area, help line and borders included.
+pub fn input_height(app: &App, width: u16) -> u16 {
+    input_rows(app, width) + 3
+}
+
+// what a moderation key does in the hint, moving the selection goes without saying
+fn moderate_label(action: Action) -> Option<&'static str> {
+    Some(match action {
+        Action::SelectNext | Action::SelectPrevious => return None,
+        Action::DeleteMessage => "delete",
+        Action::TimeoutMinute => "timeout 1m",
+        Action::TimeoutTenMinutes => "timeout 10m",
+        Action::TimeoutHour => "timeout 1h",
+        Action::TimeoutDay => "timeout 1d",
+        Action::BanAuthor => "ban",
+        Action::CopyName => "copy name",
+        Action::Reply => "reply",
+        Action::ShowThread => "thread",
+        Action::ToggleRepeats => "repeats",
+        Action::Cancel => "done",
+        action => action.name(),
+    })
+}
 
 pub fn render_message_box(app: &App, area: Rect, frame: &mut Frame) {
-    use Constraint::Length;
+    use Constraint::{Length, Min};
 
-    let layout = Layout::vertical([Length(1), Length(3)]);
+    let layout = Layout::vertical([Length(1), Min(3)]);
     let [help_area, message_box] = layout.areas(area);
     let (msg, style) = match app.message_box.mode {
-        MessageMode::Normal => (
-            vec!["Press <ctrl + q> to exit, <i> to edit".into()],
-            Style::default(),
-        ),
+        _ if matches!(app.app_mode, AppMode::Moderate) => {
+            let keymap = &app.keymaps.moderate;
+            let status = app
+                .moderation_status
+                .clone()
+                .unwrap_or_else(|| "moderating".to_string());
+            let hints: Vec<String> = keymap
+                .describe()
+                .into_iter()
+                .filter_map(|(_, action)| {
+                    moderate_label(action).map(|label| format!("<{}> {label}", keymap.hint(action)))
+                })
+                .collect();
+            (
+                vec![format!("{status}: {}", hints.join(", ")).into()],
+                Style::default().fg(Color::Yellow),
+            )
+        }
+        MessageMode::Normal => {
+            let keymap = &app.keymaps.normal;
+            let hint = |action| keymap.hint(action);
+            (
+                vec![format!(
+                    "Press <{}> to exit, <{}> to edit, <{}> for help",
+                    hint(Action::Quit),
+                    hint(Action::StartEditing),
+                    hint(Action::ToggleHelp)
+                )
+                .into()],
+                Style::default(),
+            )
+        }
         MessageMode::Editing => (
-            vec!["Press ESC to return to normal mode".into()],
+            vec!["Press ESC to return to normal mode, <tab> to complete, <up/down> for history, <ctrl + r> to search it".into()],
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+        MessageMode::HistorySearch => (
+            vec!["<enter> to use, <ctrl + r> for older, ESC to cancel".into()],
             Style::default().add_modifier(Modifier::DIM),
         ),
     };
     let text = Text::from(Line::from(msg)).patch_style(style);
     let help_message = Paragraph::new(text);
 
-    let input = Paragraph::new(app.message_box.input.as_str())
+    if let MessageMode::HistorySearch = app.message_box.mode {
+        let search = &app.history.reverse_search;
+        let found = app.history.reverse_search_match().unwrap_or_default();
+        let input = Paragraph::new(found)
+            .style(Style::default().fg(Color::Yellow))
+            .block(Block::bordered().title(format!(
+                "{}reverse-i-search: {}",
+                if search.failing { "failing " } else { "" },
+                search.query
+            )));
+        frame.render_widget(help_message, help_area);
+        frame.render_widget(input, message_box);
+        return;
+    }
+
+    let (rows, (cursor_row, cursor_column)) = app
+        .message_box
+        .input
+        .wrap(message_box.width.saturating_sub(2) as usize);
+    // scroll down just enough to keep the cursor's row in view
+    let visible_rows = input_rows(app, message_box.width) as usize;
+    let first_row = (cursor_row + 1).saturating_sub(visible_rows);
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .skip(first_row)
+        .take(visible_rows)
+        .map(Line::from)
+        .collect();
+
+    let length = app.message_box.input.char_count();
+    let counter_style = if app.message_box.is_too_long() {
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else if app.message_box.is_near_limit() {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default().add_modifier(Modifier::DIM)
+    };
+    let counter = Line::styled(format!("{length}/{MAX_MESSAGE_LENGTH}"), counter_style);
+
+    let input = Paragraph::new(lines)
         .style(match app.message_box.mode {
             MessageMode::Normal => Style::default(),
-            MessageMode::Editing => Style::default().fg(Color::Yellow),
+            _ => Style::default().fg(Color::Yellow),
         })
-        .block(Block::bordered().title("Input"));
+        .block(
+            Block::bordered()
+                .title(input_title(app))
+                .title_bottom(counter.right_aligned()),
+        );
     match app.message_box.mode {
-        MessageMode::Normal =>
+        MessageMode::Normal | MessageMode::HistorySearch =>
             // Hide the cursor. `Frame` does this by default, so we don't need to do anything here
             {}
 
@@ -45,9 +171,9 @@ pub fn render_message_box(app: &App, area: Rect, frame: &mut Frame) {
                 // Draw the cursor at the current position in the input field.
                 // This position is can be controlled via the left and right arrow key
                 (
-                    message_box.x + app.message_box.character_index as u16 + 1,
+                    message_box.x + cursor_column as u16 + 1,
                     // Move one line down, from the border to the input line
-                    message_box.y + 1,
+                    message_box.y + (cursor_row - first_row) as u16 + 1,
                 ),
             );
         }
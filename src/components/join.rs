@@ -14,19 +14,20 @@ pub fn render_join_box(app: &App, area: Rect, frame: &mut Frame) {
     let layout = Layout::vertical([Length(1), Length(3)]);
     let [help_area, input_box] = layout.areas(area);
     let (msg, style) = (
-        vec!["Press <ESC> to return to chat interface".into()],
+        vec!["Press <ESC> to return to chat".into()],
         Style::default(),
     );
     let text = Text::from(Line::from(msg)).patch_style(style);
     let help_message = Paragraph::new(text);
 
-    let input = Paragraph::new(app.join_box.channel.as_str())
+    let (visible, cursor_column) = app
+        .join_box
+        .channel
+        .scrolled(input_box.width.saturating_sub(2) as usize);
+    let input = Paragraph::new(visible)
         .style(Style::default().fg(Color::Yellow))
         .block(Block::bordered().title("Join Channel"));
-    frame.set_cursor_position((
-        input_box.x + app.join_box.character_index as u16 + 1,
-        input_box.y + 1,
-    ));
+    frame.set_cursor_position((input_box.x + cursor_column as u16 + 1, input_box.y + 1));
     frame.render_widget(help_message, help_area);
     frame.render_widget(input, input_box);
 }
@@ -1,47 +1,363 @@
 use ratatui::{
-    layout::Rect,
-    text::Line,
+    layout::{Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, List, ListDirection, ListItem},
     Frame,
 };
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, MessageInfo};
+use crate::{
+    app::{App, Arrival, ChannelInfo, MessageInfo},
+    filters::Filtered,
+    twitch::bits::{cheer_tier, cheermote_amount},
+};
 
-pub fn render_messages(app: &mut App, area: Rect, frame: &mut Frame) {
-    let current_channel = app.channels.get(app.current_channel);
-    if let Some(channel) = current_channel {
-        let message_contents: Vec<String> = channel
-            .messages
-            .iter()
-            .map(|message_info: &MessageInfo| {
-                let MessageInfo { nickname, content } = message_info;
-                format!("{nickname}: {content}")
-            })
-            .rev()
+const CHANNEL_COLORS: &[Color] = &[
+    Color::Cyan,
+    Color::Green,
+    Color::Magenta,
+    Color::Blue,
+    Color::Red,
+    Color::LightCyan,
+    Color::LightGreen,
+    Color::LightMagenta,
+    Color::LightBlue,
+    Color::LightRed,
+];
+
+// the same channel always gets the same color
+fn channel_color(channel: &str) -> Color {
+    let hash = channel.bytes().fold(0usize, |hash, b| {
+        hash.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    CHANNEL_COLORS[hash % CHANNEL_COLORS.len()]
+}
+
+// gray, purple, green, blue and red like twitch's own cheermote tiers
+const CHEER_COLORS: &[Color] = &[
+    Color::Gray,
+    Color::LightMagenta,
+    Color::LightGreen,
+    Color::LightBlue,
+    Color::LightRed,
+];
+
+fn cheer_color(amount: u64) -> Color {
+    CHEER_COLORS[cheer_tier(amount)]
+}
+
+// colors `Cheer100`-style words by their tier, keeping the rest of each span as it was
+fn color_cheermotes(line: Line<'static>) -> Line<'static> {
+    let mut spans = Vec::new();
+    for span in line.spans {
+        let mut plain = String::new();
+        for piece in span.content.split_inclusive(' ') {
+            let word = piece.trim_end();
+            let Some(amount) = cheermote_amount(word) else {
+                plain.push_str(piece);
+                continue;
+            };
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), span.style));
+            }
+            spans.push(Span::styled(
+                word.to_string(),
+                span.style
+                    .fg(cheer_color(amount))
+                    .add_modifier(Modifier::BOLD),
+            ));
+            plain.push_str(&piece[word.len()..]);
+        }
+        if !plain.is_empty() {
+            spans.push(Span::styled(plain, span.style));
+        }
+    }
+    Line { spans, ..line }
+}
+
+// splits one wrapped row into spans so buffer search matches stand out,
+// `ranges` are byte ranges in the whole message and `offset` is where the row starts in it
+fn highlight_matches(
+    row: &str,
+    offset: usize,
+    ranges: &[Range<usize>],
+    style: Style,
+) -> Line<'static> {
+    let match_style = style.bg(Color::Yellow).fg(Color::Black);
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for range in ranges {
+        // a match cut by the wrap is highlighted on both rows
+        let start = range.start.saturating_sub(offset).max(last);
+        let end = range.end.saturating_sub(offset).min(row.len());
+        if start >= end {
+            continue;
+        }
+        if start > last {
+            spans.push(Span::styled(row[last..start].to_string(), style));
+        }
+        spans.push(Span::styled(row[start..end].to_string(), match_style));
+        last = end;
+    }
+    if last < row.len() || spans.is_empty() {
+        spans.push(Span::styled(row[last..].to_string(), style));
+    }
+    Line::from(spans)
+}
+
+struct ChannelLines {
+    lines: Vec<Line<'static>>,
+    // line to scroll to for `scroll_to_message`
+    scroll_offset: Option<usize>,
+    // line index and column of each message's nickname
+    nickname_lines: Vec<(usize, u16, String)>,
+}
+
+// newest first, as the list is drawn bottom to top
+fn channel_lines(app: &App, channel: &ChannelInfo, width: u16) -> ChannelLines {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut scroll_offset = None;
+    let mut nickname_lines = Vec::new();
+
+    for message_info in channel.messages.iter().rev() {
+        if !app.is_drawn(message_info) {
+            continue;
+        }
+        let MessageInfo {
+            id,
+            nickname,
+            content,
+            filter,
+            ..
+        } = message_info;
+        let mut style = if app.focused_message.as_ref() == Some(id) {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        let mut content = match filter {
+            Filtered::Collapsed => {
+                style = style.add_modifier(Modifier::DIM);
+                "[ignored]"
+            }
+            Filtered::Dimmed => {
+                style = style.add_modifier(Modifier::DIM);
+                content.as_str()
+            }
+            Filtered::Replaced(replaced) => replaced.as_str(),
+            Filtered::Shown | Filtered::Hidden => content.as_str(),
+        }
+        .to_string();
+        // folded repeats are counted on the message they were folded into
+        if message_info.repeats > 0 && !app.expanded_repeats.contains(id) {
+            content = format!("{content} ×{}", message_info.repeats + 1);
+        }
+        if app.scroll_to_message.as_ref() == Some(id) {
+            scroll_offset = Some(lines.len());
+        }
+
+        // virtual tabs mix channels, so each message says where it is from
+        let tag = channel
+            .kind
+            .is_virtual()
+            .then(|| format!("{} ", message_info.channel));
+        let tag_width = tag.as_ref().map_or(0, |t| t.width());
+
+        let text = format!(
+            "{}{nickname}: {content}",
+            tag.as_deref().unwrap_or_default()
+        );
+        // matched on the unwrapped text, so a match can span rows
+        let tag_len = tag.as_ref().map_or(0, |t| t.len());
+        let ranges: Vec<Range<usize>> = app
+            .buffer_search
+            .match_ranges(&text[tag_len..])
+            .into_iter()
+            .map(|range| range.start + tag_len..range.end + tag_len)
             .collect();
-        let wrapped_text: Vec<String> = message_contents
+        let wrapped = textwrap::wrap(&text, width as usize);
+        // where each row starts in `text`, the whitespace dropped at a wrap is skipped
+        let mut row_start = 0;
+        let offsets: Vec<usize> = wrapped
             .iter()
-            .flat_map(|s| {
-                let mut wrap = textwrap::wrap(s, area.width as usize);
-                wrap.iter_mut()
-                    .map(|s| s.clone().into_owned())
-                    .rev()
-                    .collect::<Vec<String>>()
+            .map(|row| {
+                let offset = text[row_start..]
+                    .find(row.as_ref())
+                    .map_or(row_start, |found| row_start + found);
+                row_start = offset + row.len();
+                offset
             })
             .collect();
-        let messages: Vec<Line> = wrapped_text
+        lines.extend(
+            wrapped
+                .iter()
+                .zip(offsets)
+                .enumerate()
+                .rev()
+                .map(|(index, (s, offset))| match &tag {
+                    Some(tag) if index == 0 && s.starts_with(tag.as_str()) => {
+                        let mut line =
+                            highlight_matches(&s[tag.len()..], offset + tag.len(), &ranges, style);
+                        line.spans.insert(
+                            0,
+                            Span::styled(
+                                tag.clone(),
+                                style.fg(channel_color(&message_info.channel)),
+                            ),
+                        );
+                        line
+                    }
+                    _ => highlight_matches(s, offset, &ranges, style),
+                })
+                .map(|line| {
+                    // outside a cheer `Cheer100` is just text someone typed
+                    if message_info.bits > 0 {
+                        color_cheermotes(line)
+                    } else {
+                        line
+                    }
+                }),
+        );
+        nickname_lines.push((lines.len() - 1, tag_width as u16, nickname.clone()));
+
+        // drawn above the reply, so pushed after it
+        if let Some(parent) = &message_info.reply_parent {
+            let context = format!("↪ replying to @{}: {}", parent.nickname, parent.content);
+            let context: String = match textwrap::wrap(&context, width as usize).first() {
+                Some(first) if first.len() < context.len() => format!("{first}…"),
+                _ => context,
+            };
+            lines.push(Line::styled(context, style.add_modifier(Modifier::DIM)));
+        }
+        match message_info.arrival {
+            Arrival::FirstMessage => lines.push(Line::styled(
+                format!("★ first message from {nickname}"),
+                style.fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            )),
+            Arrival::Returning => lines.push(Line::styled(
+                format!("↺ {nickname} is back"),
+                style.fg(Color::LightCyan),
+            )),
+            Arrival::Regular => {}
+        }
+        if message_info.bits > 0 {
+            lines.push(Line::styled(
+                format!("◆ cheered {} bits", message_info.bits),
+                style
+                    .fg(cheer_color(message_info.bits))
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+    }
+
+    ChannelLines {
+        lines,
+        scroll_offset,
+        nickname_lines,
+    }
+}
+
+// once the view is split every pane is titled with its channel and the focused one stands out
+fn pane_block(app: &App, channel: &str, focused: bool) -> Block<'static> {
+    let info = app.channels.iter().find(|c| c.name == channel);
+    let filtered = info.map_or(0, |c| {
+        c.messages
             .iter()
-            .map(|s| Line::from(s.to_string()))
-            .collect();
+            .filter(|m| m.filter != Filtered::Shown)
+            .count()
+    });
+    let filtered = if filtered > 0 {
+        format!(" ({filtered} filtered)")
+    } else {
+        String::new()
+    };
+    if app.panes.count() == 1 {
+        return Block::bordered().title(format!("messages{filtered}"));
+    }
+
+    let title = if info.is_some() {
+        format!("{channel}{filtered}")
+    } else {
+        format!("{channel} (not joined)")
+    };
+    let block = Block::bordered().title(title);
+    if focused {
+        block.border_style(Style::default().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+/// Renders the focused pane, which shows the current channel.
+pub fn render_messages(app: &mut App, area: Rect, frame: &mut Frame) {
+    let current_channel = app.channels.get(app.current_channel);
+    if let Some(channel) = current_channel {
+        // wrapped to fit between the borders
+        let ChannelLines {
+            lines,
+            scroll_offset,
+            nickname_lines,
+        } = channel_lines(app, channel, area.width.saturating_sub(2));
+        let block = pane_block(app, &channel.name, true);
 
-        let messages = List::new(messages)
+        if let Some(offset) = scroll_offset {
+            *app.list_state.offset_mut() = offset;
+            app.scroll_to_message = None;
+        }
+
+        let messages = List::new(lines)
             .direction(ListDirection::BottomToTop)
-            .block(Block::bordered().title("messages"));
+            .block(block);
 
         frame.render_stateful_widget(messages, area, &mut app.list_state);
+
+        // lines are drawn from the bottom up starting at the offset
+        let inner = area.inner(Margin::new(1, 1));
+        let offset = app.list_state.offset();
+        app.click_areas.messages = inner;
+        app.click_areas.nicknames = nickname_lines
+            .into_iter()
+            .filter(|(line, column, _)| {
+                *line >= offset && line - offset < inner.height as usize && *column < inner.width
+            })
+            .map(|(line, column, nickname)| {
+                let y = inner.bottom() - 1 - (line - offset) as u16;
+                let width = (nickname.width() as u16).min(inner.width - column);
+                (Rect::new(inner.x + column, y, width, 1), nickname)
+            })
+            .collect();
     } else {
+        app.click_areas.messages = Rect::default();
+        app.click_areas.nicknames.clear();
+        let block = pane_block(app, &app.panes.focused_pane().channel, true);
         let messages: Vec<ListItem> = vec![];
-        let messages = List::new(messages).block(Block::bordered().title("messages"));
+        let messages = List::new(messages).block(block);
         frame.render_widget(messages, area);
     }
 }
+
+/// Renders a pane that isn't focused, with the channel and scroll position it remembers.
+pub fn render_pane(app: &mut App, index: usize, area: Rect, frame: &mut Frame) {
+    let Some(pane) = app.panes.panes().get(index).map(|p| (*p).clone()) else {
+        return;
+    };
+    let block = pane_block(app, &pane.channel, false);
+    let mut list_state = pane.list_state;
+
+    match app.channels.iter().find(|c| c.name == pane.channel) {
+        Some(channel) => {
+            let lines = channel_lines(app, channel, area.width.saturating_sub(2)).lines;
+            let messages = List::new(lines)
+                .direction(ListDirection::BottomToTop)
+                .block(block);
+            frame.render_stateful_widget(messages, area, &mut list_state);
+        }
+        None => frame.render_widget(List::new(Vec::<ListItem>::new()).block(block), area),
+    }
+
+    if let Some(pane) = app.panes.pane_mut(index) {
+        pane.list_state = list_state;
+    }
+}
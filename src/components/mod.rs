@@ -1,4 +1,15 @@
+pub mod buffer_search;
+pub mod chatters;
+pub mod confirm;
+pub mod filters;
+pub mod help;
 pub mod input;
 pub mod join;
 pub mod messages;
+pub mod panes;
+pub mod search;
+pub mod stats;
 pub mod tabs;
+pub mod target_picker;
+pub mod thread;
+pub mod user_card;
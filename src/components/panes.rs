@@ -0,0 +1,15 @@
+use ratatui::{layout::Rect, Frame};
+
+use crate::{app::App, components::messages};
+
+pub fn render_panes(app: &mut App, area: Rect, frame: &mut Frame) {
+    let areas = app.panes.areas(area);
+    for (index, pane_area) in areas.iter().enumerate() {
+        if index == app.panes.focused {
+            messages::render_messages(app, *pane_area, frame);
+        } else {
+            messages::render_pane(app, index, *pane_area, frame);
+        }
+    }
+    app.click_areas.panes = areas;
+}
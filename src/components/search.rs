@@ -0,0 +1,80 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::{app::App, keymap::Action, store::StoredMessage};
+
+fn result_line(result: &StoredMessage, focused: bool) -> ListItem<'static> {
+    let StoredMessage { channel, message } = result;
+    let line = Line::from(vec![
+        Span::styled(
+            message.timestamp.format("%Y-%m-%d %H:%M ").to_string(),
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+        Span::styled(format!("{channel} "), Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!("{}: ", message.nickname),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(message.content.clone()),
+    ]);
+    if focused {
+        ListItem::new(line).style(Style::default().add_modifier(Modifier::REVERSED))
+    } else {
+        ListItem::new(line)
+    }
+}
+
+pub fn render_search(app: &mut App, area: Rect, frame: &mut Frame) {
+    use Constraint::{Length, Min};
+
+    let layout = Layout::vertical([Length(1), Min(1)]);
+    let [help_area, list_area] = layout.areas(area);
+
+    frame.render_widget(Clear, area);
+
+    if let Some(context) = &app.search.context {
+        let help = Paragraph::new(format!(
+            "Press <{}> to return to results",
+            app.keymaps.search.hint(Action::Cancel)
+        ));
+        let items: Vec<ListItem> = context
+            .iter()
+            .map(|m| result_line(m, app.focused_message.as_ref() == Some(&m.message.id)))
+            .collect();
+        let list = List::new(items).block(Block::bordered().title("context"));
+        frame.render_widget(help, help_area);
+        frame.render_widget(list, list_area);
+        return;
+    }
+
+    let keymap = &app.keymaps.search;
+    let help = Paragraph::new(format!(
+        "<{}/{}> to move, <{}> to jump to message, <{}> to close",
+        keymap.hint(Action::SelectNext),
+        keymap.hint(Action::SelectPrevious),
+        keymap.hint(Action::Confirm),
+        keymap.hint(Action::Cancel)
+    ));
+    let items: Vec<ListItem> = app
+        .search
+        .results
+        .iter()
+        .map(|m| result_line(m, false))
+        .collect();
+    let title = format!(
+        "search: {} ({} results)",
+        app.search.query,
+        app.search.results.len()
+    );
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(help, help_area);
+    frame.render_stateful_widget(list, list_area, &mut app.search.list_state);
+}
@@ -0,0 +1,90 @@
+use chrono::Local;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Paragraph, Sparkline},
+    Frame,
+};
+
+use crate::{app::App, stats::ChannelStats};
+
+// entries in each of the top lists
+const TOP: usize = 5;
+
+fn top_lines(title: &'static str, top: Vec<(&str, u64)>) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::styled(
+        title,
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if top.is_empty() {
+        lines.push(Line::styled(
+            "nothing yet",
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+    lines.extend(
+        top.into_iter()
+            .map(|(name, count)| Line::from(format!("{count:>5} {name}"))),
+    );
+    lines
+}
+
+fn summary_lines(stats: &ChannelStats, rate: &[u64]) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(format!(
+            "{}/min now, {}/min peak",
+            rate.last().copied().unwrap_or_default(),
+            rate.iter().max().copied().unwrap_or_default()
+        )),
+        Line::from(format!(
+            "{} messages from {} chatters",
+            stats.messages,
+            stats.unique_chatters()
+        )),
+        Line::styled(
+            format!("{} subs, {} bits", stats.subs, stats.bits),
+            Style::default().fg(Color::Magenta),
+        ),
+        Line::default(),
+    ];
+    lines.extend(top_lines("top chatters", stats.top_chatters(TOP)));
+    lines.push(Line::default());
+    lines.extend(top_lines("top cheerers", stats.top_cheerers(TOP)));
+    lines.push(Line::default());
+    lines.extend(top_lines("top emotes", stats.top_emotes(TOP)));
+    lines.push(Line::default());
+    lines.extend(top_lines("top words", stats.top_words(TOP)));
+    lines
+}
+
+pub fn render_stats(app: &App, area: Rect, frame: &mut Frame) {
+    let channel = app
+        .channels
+        .get(app.current_channel)
+        .map(|c| c.name.clone())
+        .unwrap_or_default();
+    let block = Block::bordered().title(format!("stats {channel}"));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(stats) = app.stats.channel(&channel) else {
+        frame.render_widget(
+            Paragraph::new("no messages yet").style(Style::default().add_modifier(Modifier::DIM)),
+            inner,
+        );
+        return;
+    };
+
+    let [sparkline_area, summary_area] =
+        Layout::vertical([Constraint::Length(5), Constraint::Min(1)]).areas(inner);
+    // the newest minutes that fit, right aligned like a scrolling chart
+    let rate = stats.per_minute(Local::now());
+    let visible = &rate[rate.len().saturating_sub(sparkline_area.width as usize)..];
+    let sparkline = Sparkline::default()
+        .block(Block::default().title("messages per minute"))
+        .data(visible)
+        .style(Style::default().fg(Color::Cyan));
+    frame.render_widget(sparkline, sparkline_area);
+    frame.render_widget(Paragraph::new(summary_lines(stats, &rate)), summary_area);
+}
@@ -1,15 +1,43 @@
 use ratatui::{
     layout::Rect,
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
+    text::Line,
     widgets::Tabs,
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::App;
+use crate::app::{App, ChannelInfo};
+
+// whisper tabs with unread messages say how many
+fn tab_title(channel: &ChannelInfo) -> String {
+    if channel.unread > 0 {
+        format!("{} ({})", channel.name, channel.unread)
+    } else {
+        channel.name.clone()
+    }
+}
+
+pub fn render_tabs(app: &mut App, area: Rect, frame: &mut Frame) {
+    // the tab being looked at has nothing unread
+    if let Some(channel) = app.channels.get_mut(app.current_channel) {
+        channel.unread = 0;
+    }
 
-pub fn render_tabs(app: &App, area: Rect, frame: &mut Frame) {
     // rendering tabs
-    let titles = app.channels.iter().map(|f| f.name.clone());
+    let titles = app.channels.iter().map(|channel| {
+        let title = tab_title(channel);
+        if channel.unread > 0 {
+            Line::styled(
+                title,
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Line::from(title)
+        }
+    });
     let selected_tab_index = app.current_channel;
     let tabs = Tabs::new(titles)
         .select(selected_tab_index)
@@ -22,4 +50,17 @@ pub fn render_tabs(app: &App, area: Rect, frame: &mut Frame) {
         .padding("", "")
         .divider(" ");
     frame.render_widget(tabs, area);
+
+    // each title is followed by the one column divider
+    let mut x = area.x;
+    app.click_areas.tabs = app
+        .channels
+        .iter()
+        .map(|channel| {
+            let width = (tab_title(channel).width() as u16).min(area.right().saturating_sub(x));
+            let tab = Rect::new(x, area.y, width, area.height);
+            x = x.saturating_add(width + 1).min(area.right());
+            tab
+        })
+        .collect();
 }
@@ -0,0 +1,25 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Clear, List},
+    Frame,
+};
+
+use crate::{app::App, keymap::Action};
+
+pub fn render_target_picker(app: &mut App, area: Rect, frame: &mut Frame) {
+    let keymap = &app.keymaps.target;
+    let hint = format!(
+        "<{}/{}> to move, <{}> to pick, <{}> to cancel",
+        keymap.hint(Action::SelectNext),
+        keymap.hint(Action::SelectPrevious),
+        keymap.hint(Action::Confirm),
+        keymap.hint(Action::Cancel)
+    );
+    let list = List::new(app.target_candidates())
+        .block(Block::bordered().title("send to").title_bottom(hint))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut app.target_picker);
+}
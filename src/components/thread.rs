@@ -0,0 +1,36 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListItem},
+    Frame,
+};
+
+use crate::{app::App, keymap::Action};
+
+pub fn render_thread(app: &App, area: Rect, frame: &mut Frame) {
+    let items: Vec<ListItem> = app
+        .thread_messages()
+        .into_iter()
+        .map(|message| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    message.timestamp.format("%H:%M ").to_string(),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+                Span::styled(
+                    format!("{}: ", message.nickname),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(message.content.clone()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(Block::bordered().title("thread").title_bottom(format!(
+        "<{}> to close",
+        app.keymaps.thread.hint(Action::Cancel)
+    )));
+    frame.render_widget(Clear, area);
+    frame.render_widget(list, area);
+}
@@ -0,0 +1,149 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, List, ListDirection, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::{
+    app::App,
+    chatters::UserCard,
+    keymap::{Action, Keymap},
+};
+
+// newest first, each wrapped to the card's width
+fn message_items(app: &App, card: &UserCard, width: u16) -> Vec<ListItem<'static>> {
+    let lines: Vec<String> = match &card.stored {
+        Some(stored) => stored
+            .iter()
+            .rev()
+            .map(|m| {
+                format!(
+                    "{} {} {}",
+                    m.message.timestamp.format("%Y-%m-%d %H:%M"),
+                    m.channel,
+                    m.message.content
+                )
+            })
+            .collect(),
+        None => app
+            .channels
+            .iter()
+            .find(|c| c.name == card.channel)
+            .map(|channel| {
+                channel
+                    .messages
+                    .iter()
+                    .rev()
+                    .filter(|m| m.nickname.eq_ignore_ascii_case(&card.nickname))
+                    .map(|m| format!("{} {}", m.timestamp.format("%H:%M"), m.content))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    lines
+        .into_iter()
+        .map(|line| {
+            let wrapped: Vec<Line> = textwrap::wrap(&line, width.max(1) as usize)
+                .into_iter()
+                .map(|s| Line::from(s.into_owned()))
+                .collect();
+            ListItem::new(Text::from(wrapped))
+        })
+        .collect()
+}
+
+fn card_hint(keymap: &Keymap) -> String {
+    [
+        (Action::MentionUser, "mention"),
+        (Action::WhisperUser, "whisper"),
+        (Action::IgnoreUser, "ignore"),
+        (Action::TimeoutUser, "timeout"),
+        (Action::BanUser, "ban"),
+        (Action::ToggleHistory, "stored"),
+        (Action::Cancel, "close"),
+    ]
+    .iter()
+    .map(|&(action, label)| format!("<{}> {label}", keymap.hint(action)))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+pub fn render_user_card(app: &mut App, area: Rect, frame: &mut Frame) {
+    let Some(card) = &app.user_card else {
+        return;
+    };
+    let chatter = app
+        .channels
+        .iter()
+        .find(|c| c.name == card.channel)
+        .and_then(|c| c.chatters.get(&card.nickname));
+
+    let block = Block::bordered()
+        .title(format!("{} in {}", card.nickname, card.channel))
+        .title_bottom(card_hint(&app.keymaps.user_card));
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let [details_area, messages_area] =
+        Layout::vertical([Constraint::Length(4), Constraint::Min(1)]).areas(inner);
+
+    let mut details = match chatter {
+        Some(chatter) => {
+            let color = chatter
+                .color
+                .as_deref()
+                .and_then(|c| c.parse::<Color>().ok());
+            let name_style = Style::default()
+                .fg(color.unwrap_or(Color::Reset))
+                .add_modifier(Modifier::BOLD);
+            let badges = if chatter.badges.is_empty() {
+                "none seen".to_string()
+            } else {
+                chatter.badges.join(", ")
+            };
+            vec![
+                Line::from(vec![
+                    Span::styled(chatter.nickname.clone(), name_style),
+                    Span::raw(format!(
+                        "  {}  {}",
+                        chatter.role.name(),
+                        chatter.color.as_deref().unwrap_or("no color")
+                    )),
+                ]),
+                Line::from(format!("badges: {badges}")),
+                Line::from(format!(
+                    "first seen: {}",
+                    chatter.first_seen.format("%Y-%m-%d %H:%M:%S")
+                )),
+            ]
+        }
+        None => vec![Line::from(format!(
+            "{} has not been seen in {}",
+            card.nickname, card.channel
+        ))],
+    };
+    let source = if card.stored.is_some() {
+        "stored messages, every channel"
+    } else {
+        "messages this session"
+    };
+    details.push(match &card.error {
+        Some(error) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+        None => Line::styled(source, Style::default().add_modifier(Modifier::DIM)),
+    });
+    frame.render_widget(Paragraph::new(details), details_area);
+
+    let items = message_items(app, card, messages_area.width);
+    let list = List::new(items).direction(ListDirection::BottomToTop);
+    let mut state = ListState::default().with_offset(card.scroll);
+    frame.render_stateful_widget(list, messages_area, &mut state);
+
+    // the list clamps scrolling past the oldest message
+    if let Some(card) = app.user_card.as_mut() {
+        card.scroll = state.offset();
+    }
+}
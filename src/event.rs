@@ -1,49 +1,141 @@
-use crate::twitch::client_stream;
-use crossterm::event::KeyEvent;
+use crate::twitch::{
+    client_stream::{self, create_client_stream, ClientEvent},
+    helix::HelixEvent,
+};
+use crossterm::event::{KeyEvent, MouseEvent};
 use futures::{FutureExt, StreamExt};
-use irc::client::ClientStream;
+use irc::client::{data::Config, Client, ClientStream};
+use std::{net::SocketAddr, time::Duration};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
+// wait between attempts when connecting again fails, doubled after every failure
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
 pub enum Event {
     Client(client_stream::ClientEvent),
+    // a new connection replacing one twitch closed, the app should rejoin its channels
+    Reconnected(Box<Client>),
+    // the connection was lost and is being made again in the background
+    Disconnected,
+    // connecting again failed, the next attempt is in `retry_in`
+    ReconnectFailed { error: String, retry_in: Duration },
+    Helix(HelixEvent),
     Key(KeyEvent),
+    Mouse(MouseEvent),
     Resize,
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct EventHandler {
     sender: mpsc::UnboundedSender<Event>,
     receiver: mpsc::UnboundedReceiver<Event>,
     handler: tokio::task::JoinHandle<()>,
 }
 
+// connects until it works or the app quits, telling the app about every failed attempt
+async fn reconnect(
+    config: &Config,
+    server: Option<SocketAddr>,
+    sender: &mpsc::UnboundedSender<Event>,
+    cancel_token: &CancellationToken,
+) -> Option<(Client, ClientStream)> {
+    let mut delay = RECONNECT_DELAY;
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => return None,
+            connected = create_client_stream(config.clone(), server) => match connected {
+                Ok(connected) => return Some(connected),
+                Err(err) => sender
+                    .send(Event::ReconnectFailed {
+                        error: err.to_string(),
+                        retry_in: delay,
+                    })
+                    .ok()?,
+            }
+        }
+        tokio::select! {
+            _ = cancel_token.cancelled() => return None,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+/// Forwards irc messages as events, connecting again when twitch sends
+/// `RECONNECT` or the connection drops.
+///
+/// `server` overrides the address in `config` the same way it does for
+/// `create_client_stream`.
+pub fn spawn_irc_reader(
+    mut config: Config,
+    server: Option<SocketAddr>,
+    mut stream: ClientStream,
+    sender: mpsc::UnboundedSender<Event>,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    // the app rejoins what it has open, which may not be what the config lists
+    config.channels.clear();
+
+    tokio::spawn(async move {
+        loop {
+            let message = tokio::select! {
+                _ = cancel_token.cancelled() => break,
+                message = stream.next() => message,
+            };
+            let reconnecting = match message {
+                Some(Ok(message)) => {
+                    let event = ClientEvent::from(message);
+                    let reconnecting = matches!(event, ClientEvent::Reconnect);
+                    if sender.send(Event::Client(event)).is_err() {
+                        break;
+                    }
+                    reconnecting
+                }
+                Some(Err(_)) | None => true,
+            };
+            if !reconnecting {
+                continue;
+            }
+
+            if sender.send(Event::Disconnected).is_err() {
+                break;
+            }
+            let Some((client, new_stream)) =
+                reconnect(&config, server, &sender, &cancel_token).await
+            else {
+                break;
+            };
+            stream = new_stream;
+            if sender.send(Event::Reconnected(Box::new(client))).is_err() {
+                break;
+            }
+        }
+    })
+}
+
 // create all our handlers here,
 // irc handler for twitch to terminal events
 // key input reading from crossterm for key handling
 impl EventHandler {
-    pub fn new(mut stream: ClientStream, cloned_cancel_token: CancellationToken) -> Self {
+    pub fn new(
+        config: Config,
+        stream: ClientStream,
+        cloned_cancel_token: CancellationToken,
+    ) -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
         let _sender = sender.clone();
 
-        let irc_cancel_token = cloned_cancel_token.clone();
-        let irc_sender = sender.clone();
-
         // handle irc twitch events
-        let _irc_handle = tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    _ = irc_cancel_token.cancelled() => {
-                        break;
-                    }
-                    message = stream.next() => {
-                        if let Some(Ok(message)) = message {
-                        irc_sender.send(Event::Client(message.into())).unwrap();
-                    };
-                    }
-                }
-            }
-        });
+        let _irc_handle = spawn_irc_reader(
+            config,
+            None,
+            stream,
+            sender.clone(),
+            cloned_cancel_token.clone(),
+        );
 
         // handle key presses
         let handler = tokio::spawn(async move {
@@ -58,6 +150,7 @@ impl EventHandler {
                 Some(Ok(evt)) = crossterm_event => {
                     match evt {
                         crossterm::event::Event::Key(key) => _sender.send(Event::Key(key)).unwrap(),
+                        crossterm::event::Event::Mouse(mouse) => _sender.send(Event::Mouse(mouse)).unwrap(),
                         crossterm::event::Event::Resize(_, _) => _sender.send(Event::Resize).unwrap(),
                         _ => {}
                         }
@@ -73,6 +166,23 @@ impl EventHandler {
         }
     }
 
+    /// A sender for events produced outside the handler, such as Helix replies.
+    pub fn sender(&self) -> mpsc::UnboundedSender<Event> {
+        self.sender.clone()
+    }
+
+    /// A handler with no sources of its own, events only arrive through `sender`.
+    ///
+    /// Used to drive the app from synthetic events without a terminal or connection.
+    pub fn detached() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            sender,
+            receiver,
+            handler: tokio::spawn(async {}),
+        }
+    }
+
     // get next event from receiver
     pub async fn next(&mut self) -> Option<Event> {
         self.receiver.recv().await
@@ -0,0 +1,343 @@
+use std::fmt;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    app::{AppResult, MessageInfo},
+    settings::normalize_channel,
+};
+
+// what replaced content reads as when the rule doesn't say
+const DEFAULT_REPLACEMENT: &str = "***";
+// between the pattern and the text of a replace rule, patterns can contain spaces
+const REPLACEMENT_SEPARATOR: &str = "=>";
+
+/// How a message is shown once ignores and filter rules were applied.
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub enum Filtered {
+    #[default]
+    Shown,
+    Hidden,
+    // an ignored user's message, drawn as a single placeholder line
+    Collapsed,
+    Dimmed,
+    Replaced(String),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FilterAction {
+    Hide,
+    Dim,
+    Replace(String),
+}
+
+/// A user whose messages are not shown, everywhere or in one channel.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Ignore {
+    // lowercase login
+    pub nickname: String,
+    pub channel: Option<String>,
+}
+
+impl Ignore {
+    pub fn new(nickname: &str, channel: Option<&str>) -> Self {
+        Self {
+            nickname: nickname.trim().trim_start_matches('@').to_lowercase(),
+            channel: channel.map(normalize_channel),
+        }
+    }
+
+    /// Parses `nick` or `nick#channel`, logins can't contain a `#`.
+    pub fn parse(value: &str) -> Self {
+        match value.split_once('#') {
+            Some((nickname, channel)) => Self::new(nickname, Some(channel)),
+            None => Self::new(value, None),
+        }
+    }
+
+    fn applies(&self, message: &MessageInfo) -> bool {
+        message.nickname.eq_ignore_ascii_case(&self.nickname)
+            && self
+                .channel
+                .as_ref()
+                .is_none_or(|channel| channel.eq_ignore_ascii_case(&message.channel))
+    }
+}
+
+impl fmt::Display for Ignore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.channel {
+            Some(channel) => write!(f, "{}{channel}", self.nickname),
+            None => write!(f, "{}", self.nickname),
+        }
+    }
+}
+
+/// A case insensitive pattern matched against message content.
+///
+/// A plain keyword is just a pattern without any special characters.
+pub struct FilterRule {
+    pub pattern: Regex,
+    pub action: FilterAction,
+    // messages it matched this session
+    pub hits: usize,
+}
+
+impl FilterRule {
+    /// Parses `hide <pattern>`, `dim <pattern>` or `replace <pattern> [=> text]`.
+    ///
+    /// The pattern is the rest of the line, spaces included, up to the first `=>` of a replace.
+    pub fn parse(value: &str) -> AppResult<Self> {
+        let (action, rest) = value.trim().split_once(' ').unwrap_or((value.trim(), ""));
+        let (pattern, action) = match action {
+            "hide" => (rest, FilterAction::Hide),
+            "dim" => (rest, FilterAction::Dim),
+            "replace" => {
+                let (pattern, text) = rest
+                    .split_once(REPLACEMENT_SEPARATOR)
+                    .map_or((rest, ""), |(pattern, text)| (pattern, text.trim()));
+                let text = if text.is_empty() {
+                    DEFAULT_REPLACEMENT
+                } else {
+                    text
+                };
+                (pattern, FilterAction::Replace(text.to_string()))
+            }
+            other => return Err(format!("unknown filter action: {other}").into()),
+        };
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(format!("filter rule without a pattern: {value}").into());
+        }
+
+        Ok(Self {
+            pattern: RegexBuilder::new(pattern).case_insensitive(true).build()?,
+            action,
+            hits: 0,
+        })
+    }
+}
+
+impl fmt::Display for FilterRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.action {
+            FilterAction::Hide => write!(f, "hide {}", self.pattern),
+            FilterAction::Dim => write!(f, "dim {}", self.pattern),
+            FilterAction::Replace(text) => {
+                write!(f, "replace {} {REPLACEMENT_SEPARATOR} {text}", self.pattern)
+            }
+        }
+    }
+}
+
+/// Ignored users and filter rules, checked in that order.
+#[derive(Default)]
+pub struct Filters {
+    pub ignores: Vec<Ignore>,
+    pub rules: Vec<FilterRule>,
+    // show ignored messages as a placeholder instead of hiding them
+    pub collapse_ignored: bool,
+    // messages from ignored users this session
+    pub ignored_hits: usize,
+}
+
+impl Filters {
+    /// Filters from the config, rules that don't parse are left out and returned as errors.
+    pub fn new(
+        ignores: &[String],
+        rules: &[String],
+        collapse_ignored: bool,
+    ) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let rules = rules
+            .iter()
+            .filter_map(|rule| {
+                FilterRule::parse(rule)
+                    .map_err(|err| errors.push(format!("{rule}: {err}")))
+                    .ok()
+            })
+            .collect();
+        let filters = Self {
+            ignores: ignores.iter().map(|i| Ignore::parse(i)).collect(),
+            rules,
+            collapse_ignored,
+            ignored_hits: 0,
+        };
+        (filters, errors)
+    }
+
+    /// Adds an ignore, returning false if it was already there.
+    pub fn ignore(&mut self, ignore: Ignore) -> bool {
+        if self.ignores.contains(&ignore) {
+            return false;
+        }
+        self.ignores.push(ignore);
+        true
+    }
+
+    pub fn unignore(&mut self, ignore: &Ignore) -> bool {
+        let count = self.ignores.len();
+        self.ignores.retain(|i| i != ignore);
+        self.ignores.len() != count
+    }
+
+    /// Adds a rule, replacing an earlier rule with the same pattern.
+    pub fn add_rule(&mut self, rule: FilterRule) {
+        self.remove_rule(rule.pattern.as_str());
+        self.rules.push(rule);
+    }
+
+    pub fn remove_rule(&mut self, pattern: &str) -> bool {
+        let count = self.rules.len();
+        self.rules.retain(|r| r.pattern.as_str() != pattern);
+        self.rules.len() != count
+    }
+
+    pub fn reset_counts(&mut self) {
+        self.ignored_hits = 0;
+        for rule in self.rules.iter_mut() {
+            rule.hits = 0;
+        }
+    }
+
+    /// How `message` should be shown, counting the hit unless `count` is off.
+    pub fn check(&mut self, message: &MessageInfo, count: bool) -> Filtered {
+        if self.ignores.iter().any(|i| i.applies(message)) {
+            if count {
+                self.ignored_hits += 1;
+            }
+            return if self.collapse_ignored {
+                Filtered::Collapsed
+            } else {
+                Filtered::Hidden
+            };
+        }
+
+        let Some(rule) = self
+            .rules
+            .iter_mut()
+            .find(|r| r.pattern.is_match(&message.content))
+        else {
+            return Filtered::Shown;
+        };
+        if count {
+            rule.hits += 1;
+        }
+        match &rule.action {
+            FilterAction::Hide => Filtered::Hidden,
+            FilterAction::Dim => Filtered::Dimmed,
+            FilterAction::Replace(text) => {
+                Filtered::Replaced(rule.pattern.replace_all(&message.content, text).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(nickname: &str, channel: &str, content: &str) -> MessageInfo {
+        MessageInfo {
+            nickname: nickname.to_string(),
+            channel: channel.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn patterns_are_the_rest_of_the_line() {
+        let rule = FilterRule::parse("hide buy followers").unwrap();
+        assert_eq!(rule.action, FilterAction::Hide);
+        assert_eq!(rule.pattern.as_str(), "buy followers");
+
+        let rule = FilterRule::parse("dim  spoiler  alert ").unwrap();
+        assert_eq!(rule.pattern.as_str(), "spoiler  alert");
+    }
+
+    #[test]
+    fn replace_text_comes_after_the_separator() {
+        let rule = FilterRule::parse("replace bad word => [redacted]").unwrap();
+        assert_eq!(rule.pattern.as_str(), "bad word");
+        assert_eq!(rule.action, FilterAction::Replace("[redacted]".to_string()));
+
+        let rule = FilterRule::parse("replace bad word").unwrap();
+        assert_eq!(rule.pattern.as_str(), "bad word");
+        assert_eq!(rule.action, FilterAction::Replace("***".to_string()));
+    }
+
+    #[test]
+    fn rules_print_as_they_parse() {
+        for rule in ["hide buy followers", "dim a+b", "replace x y => z w"] {
+            assert_eq!(FilterRule::parse(rule).unwrap().to_string(), rule);
+        }
+    }
+
+    #[test]
+    fn bad_rules_are_rejected() {
+        assert!(FilterRule::parse("hide").is_err());
+        assert!(FilterRule::parse("hide   ").is_err());
+        assert!(FilterRule::parse("replace => text").is_err());
+        assert!(FilterRule::parse("mute word").is_err());
+        assert!(FilterRule::parse("hide (unclosed").is_err());
+    }
+
+    #[test]
+    fn bad_config_rules_are_skipped() {
+        let rules = ["hide (".to_string(), "dim ok".to_string()];
+        let (filters, errors) = Filters::new(&[], &rules, false);
+        assert_eq!(filters.rules.len(), 1);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn ignores_parse_with_and_without_a_channel() {
+        let ignore = Ignore::parse("@SomeOne#Chan");
+        assert_eq!(ignore.nickname, "someone");
+        assert_eq!(ignore.channel.as_deref(), Some("#chan"));
+        assert_eq!(ignore.to_string(), "someone#chan");
+        assert_eq!(Ignore::parse("someone").channel, None);
+    }
+
+    #[test]
+    fn ignores_come_before_rules() {
+        let mut filters = Filters::default();
+        filters.ignore(Ignore::new("spammer", Some("one")));
+        filters.add_rule(FilterRule::parse("dim hello").unwrap());
+
+        let ignored = message("Spammer", "#one", "hello");
+        assert_eq!(filters.check(&ignored, true), Filtered::Hidden);
+        // the ignore only applies in its channel
+        let elsewhere = message("spammer", "#two", "hello");
+        assert_eq!(filters.check(&elsewhere, true), Filtered::Dimmed);
+
+        filters.collapse_ignored = true;
+        assert_eq!(filters.check(&ignored, false), Filtered::Collapsed);
+        assert_eq!(filters.ignored_hits, 1);
+        assert_eq!(filters.rules[0].hits, 1);
+    }
+
+    #[test]
+    fn replace_rules_rewrite_every_match() {
+        let mut filters = Filters::default();
+        filters.add_rule(FilterRule::parse("replace b[ae]d => ok").unwrap());
+        let checked = filters.check(&message("a", "#one", "BAD and bed"), true);
+        assert_eq!(checked, Filtered::Replaced("ok and ok".to_string()));
+        assert_eq!(
+            filters.check(&message("a", "#one", "fine"), true),
+            Filtered::Shown
+        );
+    }
+
+    #[test]
+    fn rules_with_the_same_pattern_replace_each_other() {
+        let mut filters = Filters::default();
+        filters.add_rule(FilterRule::parse("hide spam").unwrap());
+        filters.add_rule(FilterRule::parse("dim spam").unwrap());
+        assert_eq!(filters.rules.len(), 1);
+        assert_eq!(filters.rules[0].action, FilterAction::Dim);
+        assert!(filters.remove_rule("spam"));
+        assert!(!filters.remove_rule("spam"));
+    }
+}
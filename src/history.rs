@@ -0,0 +1,235 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub channel: String,
+    pub text: String,
+}
+
+/// Ctrl-R reverse incremental search through the global history.
+#[derive(Default)]
+pub struct ReverseSearch {
+    pub query: String,
+    // index into `InputHistory::entries` of the current match
+    pub found: Option<usize>,
+    // nothing matches the query, `found` still holds the last match like readline
+    pub failing: bool,
+}
+
+/// Messages we've sent, oldest first, shared between sessions.
+pub struct InputHistory {
+    entries: Vec<HistoryEntry>,
+    max_entries: usize,
+    path: PathBuf,
+    // index into `entries` while browsing with up/down
+    position: Option<usize>,
+    // whatever was typed before browsing started, restored when scrolling back down
+    draft: String,
+    pub reverse_search: ReverseSearch,
+}
+
+impl InputHistory {
+    /// Loads history from `path`, a missing or unreadable file just starts empty.
+    pub fn load(path: PathBuf, max_entries: usize) -> Self {
+        let entries = fs::read_to_string(&path)
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut history = Self {
+            entries,
+            max_entries,
+            path,
+            position: None,
+            draft: String::new(),
+            reverse_search: ReverseSearch::default(),
+        };
+        history.truncate();
+        history
+    }
+
+    fn truncate(&mut self) {
+        if self.entries.len() > self.max_entries {
+            let excess = self.entries.len() - self.max_entries;
+            self.entries.drain(..excess);
+        }
+    }
+
+    pub fn push(&mut self, channel: &str, text: &str) -> io::Result<()> {
+        self.reset_navigation();
+
+        // don't fill the history up with the same line sent over and over
+        let last = self.entries.iter().rev().find(|e| e.channel == channel);
+        if last.is_some_and(|e| e.text == text) {
+            return Ok(());
+        }
+
+        self.entries.push(HistoryEntry {
+            channel: channel.to_string(),
+            text: text.to_string(),
+        });
+        self.truncate();
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = fs::File::create(&self.path)?;
+        for entry in &self.entries {
+            let line = serde_json::to_string(entry).map_err(io::Error::other)?;
+            writeln!(file, "{line}")?;
+        }
+        Ok(())
+    }
+
+    pub fn reset_navigation(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+
+    /// Steps to an older entry, only looking at `channel` unless it is `None`.
+    pub fn previous(&mut self, channel: Option<&str>, current_input: &str) -> Option<String> {
+        let start = match self.position {
+            Some(position) => position,
+            None => {
+                self.draft = current_input.to_string();
+                self.entries.len()
+            }
+        };
+
+        let found = self.entries[..start]
+            .iter()
+            .rposition(|e| channel.is_none_or(|c| e.channel == c))?;
+        self.position = Some(found);
+        Some(self.entries[found].text.clone())
+    }
+
+    /// Steps to a newer entry, returning the original draft once past the newest.
+    pub fn next(&mut self, channel: Option<&str>) -> Option<String> {
+        let position = self.position?;
+
+        let found = self.entries[position + 1..]
+            .iter()
+            .position(|e| channel.is_none_or(|c| e.channel == c))
+            .map(|offset| position + 1 + offset);
+        match found {
+            Some(found) => {
+                self.position = Some(found);
+                Some(self.entries[found].text.clone())
+            }
+            None => {
+                self.position = None;
+                Some(std::mem::take(&mut self.draft))
+            }
+        }
+    }
+
+    pub fn start_reverse_search(&mut self) {
+        self.reverse_search = ReverseSearch::default();
+    }
+
+    // finds the newest entry containing the query, older than `before`
+    fn search_from(&mut self, before: usize) {
+        let query = &self.reverse_search.query;
+        let found = self.entries[..before]
+            .iter()
+            .rposition(|e| e.text.contains(query.as_str()));
+        // keep showing the previous match if nothing older is found, like bash does
+        if found.is_some() || query.is_empty() {
+            self.reverse_search.found = found;
+            self.reverse_search.failing = false;
+        } else {
+            self.reverse_search.failing = true;
+        }
+    }
+
+    pub fn reverse_search_push(&mut self, new_char: char) {
+        self.reverse_search.query.push(new_char);
+        let before = self
+            .reverse_search
+            .found
+            .map_or(self.entries.len(), |found| found + 1);
+        self.search_from(before);
+    }
+
+    pub fn reverse_search_pop(&mut self) {
+        self.reverse_search.query.pop();
+        self.search_from(self.entries.len());
+    }
+
+    /// Ctrl-R again while searching, moves to an older match.
+    pub fn reverse_search_older(&mut self) {
+        let before = self.reverse_search.found.unwrap_or(self.entries.len());
+        self.search_from(before);
+    }
+
+    pub fn reverse_search_match(&self) -> Option<&str> {
+        self.reverse_search
+            .found
+            .map(|found| self.entries[found].text.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(texts: &[&str]) -> InputHistory {
+        let mut history = InputHistory::load(PathBuf::new(), 10);
+        history.entries = texts
+            .iter()
+            .map(|text| HistoryEntry {
+                channel: "#chan".to_string(),
+                text: text.to_string(),
+            })
+            .collect();
+        history
+    }
+
+    fn search(history: &mut InputHistory, query: &str) {
+        history.start_reverse_search();
+        query.chars().for_each(|c| history.reverse_search_push(c));
+    }
+
+    #[test]
+    fn search_finds_the_newest_match() {
+        let mut history = history(&["hello there", "help", "hello again"]);
+        search(&mut history, "hel");
+        assert_eq!(history.reverse_search_match(), Some("hello again"));
+        history.reverse_search_older();
+        assert_eq!(history.reverse_search_match(), Some("help"));
+        assert!(!history.reverse_search.failing);
+    }
+
+    #[test]
+    fn search_without_a_match_is_failing() {
+        let mut history = history(&["hello", "bye"]);
+        search(&mut history, "hex");
+        assert!(history.reverse_search.failing);
+        assert_eq!(history.reverse_search_match(), Some("hello"));
+
+        history.reverse_search_pop();
+        assert!(!history.reverse_search.failing);
+    }
+
+    #[test]
+    fn search_past_the_oldest_match_is_failing() {
+        let mut history = history(&["bye", "hello"]);
+        search(&mut history, "b");
+        history.reverse_search_older();
+        assert!(history.reverse_search.failing);
+        assert_eq!(history.reverse_search_match(), Some("bye"));
+    }
+}
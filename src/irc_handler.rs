@@ -1,12 +1,37 @@
 use crate::{
-    app::{App, AppResult, MessageInfo},
-    twitch::client_stream::ClientEvent,
+    app::{local_message_id, App, AppResult, MessageInfo},
+    twitch::{
+        bits::message_bits,
+        client_stream::{arrival, emote_names, message_time, reply_parent, ClientEvent},
+    },
 };
 
 pub fn handle_irc_messages(irc_event: ClientEvent, app: &mut App) -> AppResult<()> {
+    let logged = app.logger.log_event(&irc_event);
+    app.check_log(logged);
+
     match irc_event {
-        ClientEvent::Privmsg(channel, msg, nickname) => {
-            let mut chat_message = MessageInfo::default();
+        ClientEvent::Privmsg(channel, msg, nickname, tags) => {
+            let mut chat_message = MessageInfo {
+                id: tags.get("id").cloned().unwrap_or_else(local_message_id),
+                channel: channel.clone(),
+                user_id: tags.get("user-id").cloned().unwrap_or_default(),
+                timestamp: message_time(&tags),
+                reply_parent: reply_parent(&tags),
+                bits: message_bits(&tags),
+                arrival: arrival(&tags),
+                ..Default::default()
+            };
+            let emotes = emote_names(&msg, &tags);
+            if let Some(nick) = &nickname {
+                app.saw_chatter_message(&channel, nick, &tags);
+                let stats = app.stats.channel_mut(&channel);
+                stats.record_message(nick, &msg, &emotes, chat_message.timestamp);
+                if chat_message.bits > 0 {
+                    stats.record_cheer(nick, chat_message.bits);
+                }
+            }
+            app.remember_emotes(emotes);
             if let Some(nick) = nickname {
                 chat_message.content = msg;
                 chat_message.nickname = nick;
@@ -14,10 +39,45 @@ pub fn handle_irc_messages(irc_event: ClientEvent, app: &mut App) -> AppResult<(
                 chat_message.content = msg;
                 chat_message.nickname = "UNKNOWN".to_string();
             }
-            app.add_chat_message(channel, chat_message);
+            app.add_chat_message(channel, chat_message)?;
+        }
+        ClientEvent::Whisper(nickname, msg, tags) => {
+            let whisper = MessageInfo {
+                id: tags
+                    .get("message-id")
+                    .cloned()
+                    .unwrap_or_else(local_message_id),
+                channel: format!("@{nickname}"),
+                user_id: tags.get("user-id").cloned().unwrap_or_default(),
+                nickname,
+                content: msg,
+                timestamp: message_time(&tags),
+                ..Default::default()
+            };
+            app.add_whisper(whisper, true)?;
+        }
+        ClientEvent::UserNotice(channel, _, tags) => {
+            // a gift bomb is followed by a `subgift` for every sub in it
+            if let Some("sub" | "resub" | "subgift") = tags.get("msg-id").map(String::as_str) {
+                app.stats.channel_mut(&channel).subs += 1;
+            }
         }
-        ClientEvent::Join(channel) => {
-            app.on_join_channel(channel);
+        ClientEvent::Notice(channel, msg, _) => app.add_notice(&channel, &msg),
+        ClientEvent::Welcome => app.join_layout_channels(),
+        ClientEvent::Join(channel, nickname) => {
+            if app.is_own_nickname(&nickname) {
+                app.on_join_channel(channel.clone());
+            }
+            app.update_chatter(&channel, &nickname, None);
+        }
+        // our own part already closed the tab
+        ClientEvent::Leave(channel, nickname) if !app.is_own_nickname(&nickname) => {
+            app.remove_chatter(&channel, &nickname);
+        }
+        ClientEvent::Names(channel, nicknames) => {
+            for nickname in nicknames {
+                app.update_chatter(&channel, &nickname, None);
+            }
         }
         _ => {}
     }
@@ -1,57 +1,65 @@
 use crate::{
-    app::{App, AppMode, AppResult},
+    app::{App, AppMode, AppResult, ChannelKind, UserCardCommand},
+    keymap::{Action, KeyResult},
     messagebox::MessageMode,
+    text_input::TextInput,
 };
 use crossterm::event::KeyEventKind;
-use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::Direction,
+};
+
+// percent a pane grows or shrinks by per key press
+const PANE_RESIZE_STEP: i16 = 5;
 
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
     if key_event.kind == KeyEventKind::Press {
+        // any other key accepts the completion being cycled through
+        if !matches!(key_event.code, KeyCode::Tab | KeyCode::BackTab) {
+            app.completion = None;
+        }
+
         match app.app_mode {
             AppMode::Normal => {
                 match app.message_box.mode {
-                    MessageMode::Normal => match key_event.code {
-                        // Exit application on `Ctrl-c` or `Ctrl-q`
-                        KeyCode::Char('q') | KeyCode::Char('c') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                app.quit();
-                            }
+                    MessageMode::Normal => {
+                        if let KeyResult::Action(action) = app.keymaps.normal.feed(key_event) {
+                            perform_action(action, app)?;
                         }
-                        KeyCode::Char('x') => {
-                            app.leave_current_channel();
+                    }
+
+                    MessageMode::Editing => match key_event.code {
+                        KeyCode::Tab => app.complete(false),
+                        KeyCode::BackTab => app.complete(true),
+                        KeyCode::Char('r') if key_event.modifiers == KeyModifiers::CONTROL => {
+                            app.start_history_search();
                         }
-                        KeyCode::Char('\\') => app.app_mode = AppMode::Joining,
-                        KeyCode::Char('j') | KeyCode::Down => {
-                            let offset = app.list_state.offset();
-                            *app.list_state.offset_mut() = offset.saturating_sub(1);
+                        // shift browses every channel's history instead of just this one
+                        KeyCode::Up => {
+                            app.history_previous(key_event.modifiers == KeyModifiers::SHIFT)
                         }
-                        KeyCode::Char('k') | KeyCode::Up => {
-                            let offset = app.list_state.offset();
-                            *app.list_state.offset_mut() = offset.saturating_add(1);
+                        KeyCode::Down => {
+                            app.history_next(key_event.modifiers == KeyModifiers::SHIFT)
                         }
-                        KeyCode::Tab => app.next_channel(),
-                        // enter edit mode
-                        KeyCode::Char('i') => app.message_box.mode = MessageMode::Editing,
-                        _ => {}
-                    },
-
-                    MessageMode::Editing => match key_event.code {
-                        KeyCode::Char(to_insert) => app.message_box.enter_char(to_insert),
                         KeyCode::Enter => {
-                            app.send_chat_message();
-                        }
-                        KeyCode::Backspace => {
-                            app.message_box.delete_char();
+                            app.send_chat_message()?;
                         }
                         KeyCode::Esc => {
+                            app.cancel_reply();
                             app.message_box.mode = MessageMode::Normal;
                         }
-                        KeyCode::Right => {
-                            app.message_box.move_cursor_right();
-                        }
-                        KeyCode::Left => {
-                            app.message_box.move_cursor_left();
+                        _ => handle_text_input(key_event, &mut app.message_box.input),
+                    },
+
+                    MessageMode::HistorySearch => match key_event.code {
+                        KeyCode::Char('r') if key_event.modifiers == KeyModifiers::CONTROL => {
+                            app.history.reverse_search_older();
                         }
+                        KeyCode::Char(to_insert) => app.history.reverse_search_push(to_insert),
+                        KeyCode::Backspace => app.history.reverse_search_pop(),
+                        KeyCode::Enter => app.finish_history_search(true),
+                        KeyCode::Esc => app.finish_history_search(false),
                         _ => {}
                     },
                 }
@@ -65,19 +73,252 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
                 KeyCode::Enter => {
                     app.join_channel();
                 }
-                KeyCode::Char(to_insert) => app.join_box.enter_char(to_insert),
-                KeyCode::Backspace => {
-                    app.join_box.delete_char();
+                _ => handle_text_input(key_event, &mut app.join_box.channel),
+            },
+
+            AppMode::BufferSearch => match key_event.code {
+                KeyCode::Esc => {
+                    app.clear_buffer_search();
+                    app.app_mode = AppMode::Normal;
+                }
+                // keep the highlights around so n/N can be used
+                KeyCode::Enter => app.app_mode = AppMode::Normal,
+                KeyCode::Char('r') if key_event.modifiers == KeyModifiers::CONTROL => {
+                    app.buffer_search.toggle_regex();
+                    app.update_buffer_search();
+                }
+                KeyCode::Char('t') if key_event.modifiers == KeyModifiers::CONTROL => {
+                    app.buffer_search.toggle_case();
+                    app.update_buffer_search();
                 }
-                KeyCode::Right => {
-                    app.join_box.move_cursor_right();
+                KeyCode::Char(to_insert) => {
+                    app.buffer_search.enter_char(to_insert);
+                    app.update_buffer_search();
                 }
-                KeyCode::Left => {
-                    app.join_box.move_cursor_left();
+                KeyCode::Backspace => {
+                    app.buffer_search.delete_char();
+                    app.update_buffer_search();
                 }
                 _ => {}
             },
+
+            AppMode::Search => {
+                if let KeyResult::Action(action) = app.keymaps.search.feed(key_event) {
+                    perform_action(action, app)?;
+                }
+            }
+
+            AppMode::Moderate => {
+                if let KeyResult::Action(action) = app.keymaps.moderate.feed(key_event) {
+                    perform_action(action, app)?;
+                }
+            }
+
+            AppMode::Thread => {
+                if let KeyResult::Action(action) = app.keymaps.thread.feed(key_event) {
+                    perform_action(action, app)?;
+                }
+            }
+
+            AppMode::Filters => {
+                if let KeyResult::Action(action) = app.keymaps.filters.feed(key_event) {
+                    perform_action(action, app)?;
+                }
+            }
+
+            AppMode::ConfirmBan | AppMode::ConfirmLeave => {
+                if let KeyResult::Action(action) = app.keymaps.confirm.feed(key_event) {
+                    perform_action(action, app)?;
+                }
+            }
+
+            // typing filters the list, so anything unbound is text
+            AppMode::Chatters => match app.keymaps.chatters.feed(key_event) {
+                KeyResult::Action(action) => perform_action(action, app)?,
+                KeyResult::Pending => {}
+                KeyResult::Unbound => match key_event.code {
+                    KeyCode::Backspace => app.chatter_panel.delete_char(),
+                    KeyCode::Char(to_insert) => app.chatter_panel.enter_char(to_insert),
+                    _ => {}
+                },
+            },
+
+            AppMode::ChooseTarget => {
+                if let KeyResult::Action(action) = app.keymaps.target.feed(key_event) {
+                    perform_action(action, app)?;
+                }
+            }
+
+            AppMode::UserCard => {
+                if let KeyResult::Action(action) = app.keymaps.user_card.feed(key_event) {
+                    perform_action(action, app)?;
+                }
+            }
+
+            // any key that doesn't scroll closes the help
+            AppMode::Help => match app.keymaps.help.feed(key_event) {
+                KeyResult::Action(action) => perform_action(action, app)?,
+                KeyResult::Pending => {}
+                KeyResult::Unbound => app.app_mode = AppMode::Normal,
+            },
         }
     }
     Ok(())
 }
+
+fn perform_action(action: Action, app: &mut App) -> AppResult<()> {
+    match action {
+        Action::Quit => app.quit(),
+        Action::LeaveChannel => app.request_leave_channel(),
+        Action::ReopenChannel => app.reopen_channel(),
+        Action::OpenJoin => app.app_mode = AppMode::Joining,
+        Action::ScrollUp => match app.app_mode {
+            AppMode::Help => app.help_scroll = app.help_scroll.saturating_sub(1),
+            AppMode::UserCard => {
+                if let Some(card) = app.user_card.as_mut() {
+                    card.scroll = card.scroll.saturating_add(1);
+                }
+            }
+            _ => {
+                let offset = app.list_state.offset();
+                *app.list_state.offset_mut() = offset.saturating_add(1);
+            }
+        },
+        Action::ScrollDown => match app.app_mode {
+            AppMode::Help => app.help_scroll = app.help_scroll.saturating_add(1),
+            AppMode::UserCard => {
+                if let Some(card) = app.user_card.as_mut() {
+                    card.scroll = card.scroll.saturating_sub(1);
+                }
+            }
+            _ => {
+                let offset = app.list_state.offset();
+                *app.list_state.offset_mut() = offset.saturating_sub(1);
+            }
+        },
+        // the list clamps this to the oldest message when it renders
+        Action::ScrollTop => *app.list_state.offset_mut() = usize::MAX,
+        Action::ScrollBottom => *app.list_state.offset_mut() = 0,
+        Action::NextChannel => app.next_channel(),
+        Action::PreviousChannel => app.previous_channel(),
+        Action::StartEditing => app.message_box.mode = MessageMode::Editing,
+        Action::StartBufferSearch => {
+            app.clear_buffer_search();
+            app.app_mode = AppMode::BufferSearch;
+        }
+        Action::NextMatch => app.jump_to_match(true),
+        Action::PreviousMatch => app.jump_to_match(false),
+        Action::ClearSearch => app.clear_buffer_search(),
+        Action::ToggleHelp => {
+            app.help_scroll = 0;
+            app.app_mode = AppMode::Help;
+        }
+        Action::ToggleMouse => app.mouse_capture = !app.mouse_capture,
+        Action::ToggleChatters => app.toggle_chatter_panel(),
+        Action::ToggleStats => app.stats_visible = !app.stats_visible,
+        Action::OpenAllChannels => app.open_virtual_tab(ChannelKind::AllChannels),
+        Action::OpenMentions => app.open_virtual_tab(ChannelKind::Mentions),
+        Action::OpenHighlights => app.open_virtual_tab(ChannelKind::Highlights),
+        Action::OpenFirstChatters => app.open_virtual_tab(ChannelKind::FirstChatters),
+        Action::SplitVertical => app.split_pane(Direction::Horizontal),
+        Action::SplitHorizontal => app.split_pane(Direction::Vertical),
+        Action::NextPane => app.next_pane(),
+        Action::PreviousPane => app.previous_pane(),
+        Action::ClosePane => app.close_pane(),
+        Action::GrowPane => app.resize_pane(PANE_RESIZE_STEP),
+        Action::ShrinkPane => app.resize_pane(-PANE_RESIZE_STEP),
+        Action::SaveLayout => app.save_layout(),
+        // shared by every mode with a list or a question
+        Action::SelectNext => match app.app_mode {
+            AppMode::Moderate => app.select_message(true),
+            AppMode::Chatters => {
+                let count = app.visible_chatters().len();
+                app.chatter_panel.select_next(count);
+            }
+            AppMode::ChooseTarget => app.target_picker.select_next(),
+            _ => app.search.select_next(),
+        },
+        Action::SelectPrevious => match app.app_mode {
+            AppMode::Moderate => app.select_message(false),
+            AppMode::Chatters => app.chatter_panel.select_previous(),
+            AppMode::ChooseTarget => app.target_picker.select_previous(),
+            _ => app.search.select_previous(),
+        },
+        Action::Confirm => match app.app_mode {
+            AppMode::Chatters => app.open_selected_chatter(),
+            AppMode::ChooseTarget => app.choose_target()?,
+            AppMode::ConfirmBan => app.finish_ban(true),
+            AppMode::ConfirmLeave => {
+                app.leave_current_channel();
+                app.app_mode = AppMode::Normal;
+            }
+            _ => app.open_search_result(),
+        },
+        Action::Cancel => match app.app_mode {
+            AppMode::Moderate => app.stop_moderating(),
+            AppMode::UserCard => app.close_user_card(),
+            AppMode::Thread => app.close_thread(),
+            AppMode::Filters => app.close_filters(),
+            AppMode::ConfirmBan => app.finish_ban(false),
+            AppMode::Chatters | AppMode::ChooseTarget | AppMode::ConfirmLeave => {
+                app.app_mode = AppMode::Normal
+            }
+            _ => app.close_search(),
+        },
+        Action::Moderate => app.start_moderating(),
+        Action::DeleteMessage => app.delete_selected(),
+        Action::TimeoutMinute => app.timeout_selected(60),
+        Action::TimeoutTenMinutes => app.timeout_selected(10 * 60),
+        Action::TimeoutHour => app.timeout_selected(60 * 60),
+        Action::TimeoutDay => app.timeout_selected(24 * 60 * 60),
+        Action::BanAuthor => app.request_ban_selected(),
+        Action::CopyName => app.copy_selected_name(),
+        Action::Reply => app.reply_to_selected(),
+        Action::ShowThread => app.show_selected_thread(),
+        Action::ToggleRepeats => app.toggle_selected_repeats(),
+        Action::ToggleHistory => app.toggle_user_card_history(),
+        Action::MentionUser => app.user_card_command(UserCardCommand::Mention),
+        Action::WhisperUser => app.user_card_command(UserCardCommand::Whisper),
+        Action::IgnoreUser => app.user_card_command(UserCardCommand::Ignore),
+        Action::TimeoutUser => app.user_card_command(UserCardCommand::Timeout),
+        Action::BanUser => app.user_card_command(UserCardCommand::Ban),
+    }
+    Ok(())
+}
+
+/// Readline style editing keys shared by every text prompt.
+fn handle_text_input(key_event: KeyEvent, input: &mut TextInput) {
+    let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key_event.modifiers.contains(KeyModifiers::ALT);
+
+    match key_event.code {
+        KeyCode::Char('a') if ctrl => input.move_to_start(),
+        KeyCode::Char('e') if ctrl => input.move_to_end(),
+        KeyCode::Char('b') if ctrl => input.move_cursor_left(),
+        KeyCode::Char('f') if ctrl => input.move_cursor_right(),
+        KeyCode::Char('d') if ctrl => input.delete_char_forward(),
+        KeyCode::Char('h') if ctrl => input.delete_char(),
+        KeyCode::Char('w') if ctrl => input.kill_word_back(),
+        KeyCode::Char('u') if ctrl => input.kill_to_start(),
+        KeyCode::Char('k') if ctrl => input.kill_to_end(),
+        KeyCode::Char('y') if ctrl => input.yank(),
+        KeyCode::Char('z') | KeyCode::Char('_') if ctrl => input.undo(),
+        KeyCode::Char('b') if alt => input.move_word_left(),
+        KeyCode::Char('f') if alt => input.move_word_right(),
+        KeyCode::Char('d') if alt => input.kill_word_forward(),
+        KeyCode::Char('y') if alt => input.yank_pop(),
+        KeyCode::Char('z') if alt => input.redo(),
+        KeyCode::Backspace if alt || ctrl => input.kill_word_back(),
+        KeyCode::Char(_) if ctrl || alt => {}
+        KeyCode::Char(to_insert) => input.enter_char(to_insert),
+        KeyCode::Backspace => input.delete_char(),
+        KeyCode::Delete => input.delete_char_forward(),
+        KeyCode::Left if ctrl || alt => input.move_word_left(),
+        KeyCode::Right if ctrl || alt => input.move_word_right(),
+        KeyCode::Left => input.move_cursor_left(),
+        KeyCode::Right => input.move_cursor_right(),
+        KeyCode::Home => input.move_to_start(),
+        KeyCode::End => input.move_to_end(),
+        _ => {}
+    }
+}
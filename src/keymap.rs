@@ -0,0 +1,754 @@
+use std::fmt;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Everything a key can be bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Quit,
+    LeaveChannel,
+    ReopenChannel,
+    OpenJoin,
+    ScrollUp,
+    ScrollDown,
+    ScrollTop,
+    ScrollBottom,
+    NextChannel,
+    PreviousChannel,
+    StartEditing,
+    StartBufferSearch,
+    NextMatch,
+    PreviousMatch,
+    ClearSearch,
+    ToggleHelp,
+    ToggleMouse,
+    ToggleChatters,
+    ToggleStats,
+    OpenAllChannels,
+    OpenMentions,
+    OpenHighlights,
+    OpenFirstChatters,
+    SplitVertical,
+    SplitHorizontal,
+    NextPane,
+    PreviousPane,
+    ClosePane,
+    GrowPane,
+    ShrinkPane,
+    SaveLayout,
+    SelectNext,
+    SelectPrevious,
+    Confirm,
+    Cancel,
+    Moderate,
+    DeleteMessage,
+    TimeoutMinute,
+    TimeoutTenMinutes,
+    TimeoutHour,
+    TimeoutDay,
+    BanAuthor,
+    CopyName,
+    Reply,
+    ShowThread,
+    ToggleRepeats,
+    ToggleHistory,
+    MentionUser,
+    WhisperUser,
+    IgnoreUser,
+    TimeoutUser,
+    BanUser,
+}
+
+impl Action {
+    const ALL: &'static [Action] = &[
+        Action::Quit,
+        Action::LeaveChannel,
+        Action::ReopenChannel,
+        Action::OpenJoin,
+        Action::ScrollUp,
+        Action::ScrollDown,
+        Action::ScrollTop,
+        Action::ScrollBottom,
+        Action::NextChannel,
+        Action::PreviousChannel,
+        Action::StartEditing,
+        Action::StartBufferSearch,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::ClearSearch,
+        Action::ToggleHelp,
+        Action::ToggleMouse,
+        Action::ToggleChatters,
+        Action::ToggleStats,
+        Action::OpenAllChannels,
+        Action::OpenMentions,
+        Action::OpenHighlights,
+        Action::OpenFirstChatters,
+        Action::SplitVertical,
+        Action::SplitHorizontal,
+        Action::NextPane,
+        Action::PreviousPane,
+        Action::ClosePane,
+        Action::GrowPane,
+        Action::ShrinkPane,
+        Action::SaveLayout,
+        Action::SelectNext,
+        Action::SelectPrevious,
+        Action::Confirm,
+        Action::Cancel,
+        Action::Moderate,
+        Action::DeleteMessage,
+        Action::TimeoutMinute,
+        Action::TimeoutTenMinutes,
+        Action::TimeoutHour,
+        Action::TimeoutDay,
+        Action::BanAuthor,
+        Action::CopyName,
+        Action::Reply,
+        Action::ShowThread,
+        Action::ToggleRepeats,
+        Action::ToggleHistory,
+        Action::MentionUser,
+        Action::WhisperUser,
+        Action::IgnoreUser,
+        Action::TimeoutUser,
+        Action::BanUser,
+    ];
+
+    /// Name used for the action in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::LeaveChannel => "leave_channel",
+            Action::ReopenChannel => "reopen_channel",
+            Action::OpenJoin => "open_join",
+            Action::ScrollUp => "scroll_up",
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollTop => "scroll_top",
+            Action::ScrollBottom => "scroll_bottom",
+            Action::NextChannel => "next_channel",
+            Action::PreviousChannel => "previous_channel",
+            Action::StartEditing => "start_editing",
+            Action::StartBufferSearch => "buffer_search",
+            Action::NextMatch => "next_match",
+            Action::PreviousMatch => "previous_match",
+            Action::ClearSearch => "clear_search",
+            Action::ToggleHelp => "help",
+            Action::ToggleMouse => "toggle_mouse",
+            Action::ToggleChatters => "toggle_chatters",
+            Action::ToggleStats => "toggle_stats",
+            Action::OpenAllChannels => "open_all_channels",
+            Action::OpenMentions => "open_mentions",
+            Action::OpenHighlights => "open_highlights",
+            Action::OpenFirstChatters => "open_first_chatters",
+            Action::SplitVertical => "split_vertical",
+            Action::SplitHorizontal => "split_horizontal",
+            Action::NextPane => "next_pane",
+            Action::PreviousPane => "previous_pane",
+            Action::ClosePane => "close_pane",
+            Action::GrowPane => "grow_pane",
+            Action::ShrinkPane => "shrink_pane",
+            Action::SaveLayout => "save_layout",
+            Action::SelectNext => "select_next",
+            Action::SelectPrevious => "select_previous",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::Moderate => "moderate",
+            Action::DeleteMessage => "delete_message",
+            Action::TimeoutMinute => "timeout_minute",
+            Action::TimeoutTenMinutes => "timeout_ten_minutes",
+            Action::TimeoutHour => "timeout_hour",
+            Action::TimeoutDay => "timeout_day",
+            Action::BanAuthor => "ban_author",
+            Action::CopyName => "copy_name",
+            Action::Reply => "reply",
+            Action::ShowThread => "show_thread",
+            Action::ToggleRepeats => "toggle_repeats",
+            Action::ToggleHistory => "toggle_history",
+            Action::MentionUser => "mention",
+            Action::WhisperUser => "whisper",
+            Action::IgnoreUser => "ignore",
+            Action::TimeoutUser => "timeout",
+            Action::BanUser => "ban",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit twi-rs",
+            Action::LeaveChannel => "leave the current channel",
+            Action::ReopenChannel => "reopen the last channel left",
+            Action::OpenJoin => "join a channel",
+            Action::ScrollUp => "scroll to older messages",
+            Action::ScrollDown => "scroll to newer messages",
+            Action::ScrollTop => "scroll to the oldest message",
+            Action::ScrollBottom => "scroll to the newest message",
+            Action::NextChannel => "switch to the next channel",
+            Action::PreviousChannel => "switch to the previous channel",
+            Action::StartEditing => "write a message",
+            Action::StartBufferSearch => "search this channel",
+            Action::NextMatch => "go to the next older search match",
+            Action::PreviousMatch => "go to the next newer search match",
+            Action::ClearSearch => "clear search highlights",
+            Action::ToggleHelp => "show this help",
+            Action::ToggleMouse => "toggle mouse capture for native text selection",
+            Action::ToggleChatters => "show or hide the chatter list",
+            Action::ToggleStats => "show or hide the chat statistics",
+            Action::OpenAllChannels => "show messages from every channel",
+            Action::OpenMentions => "show messages mentioning you",
+            Action::OpenHighlights => "show messages with highlight words",
+            Action::OpenFirstChatters => "show first messages of new chatters",
+            Action::SplitVertical => "split the pane side by side",
+            Action::SplitHorizontal => "split the pane top and bottom",
+            Action::NextPane => "focus the next pane",
+            Action::PreviousPane => "focus the previous pane",
+            Action::ClosePane => "close the focused pane",
+            Action::GrowPane => "make the focused pane bigger",
+            Action::ShrinkPane => "make the focused pane smaller",
+            Action::SaveLayout => "save the pane layout",
+            Action::SelectNext => "select the next item",
+            Action::SelectPrevious => "select the previous item",
+            Action::Confirm => "open or confirm the selected item",
+            Action::Cancel => "close or cancel",
+            Action::Moderate => "select messages to moderate",
+            Action::DeleteMessage => "delete the selected message",
+            Action::TimeoutMinute => "time out the author for a minute",
+            Action::TimeoutTenMinutes => "time out the author for ten minutes",
+            Action::TimeoutHour => "time out the author for an hour",
+            Action::TimeoutDay => "time out the author for a day",
+            Action::BanAuthor => "ban the author, after confirming",
+            Action::CopyName => "copy the author's name",
+            Action::Reply => "reply to the selected message",
+            Action::ShowThread => "show the conversation the message is part of",
+            Action::ToggleRepeats => "expand or fold the repeats of the message",
+            Action::ToggleHistory => "switch between this session and every stored message",
+            Action::MentionUser => "mention the user in the input",
+            Action::WhisperUser => "whisper the user",
+            Action::IgnoreUser => "ignore the user",
+            Action::TimeoutUser => "time out the user",
+            Action::BanUser => "ban the user",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Action::ALL.iter().copied().find(|a| a.name() == name)
+    }
+}
+
+/// A single key press, modifiers included.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers;
+        // `N` already says shift was held
+        if let KeyCode::Char(_) | KeyCode::BackTab = event.code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Key {
+            code: event.code,
+            modifiers,
+        }
+    }
+}
+
+impl Key {
+    fn parse(token: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = token;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if lower.starts_with("ctrl-") && rest.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+            } else if lower.starts_with("alt-") && rest.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+            } else if lower.starts_with("shift-") && rest.len() > 6 {
+                modifiers |= KeyModifiers::SHIFT;
+            } else {
+                break;
+            }
+            rest = &rest[rest.find('-').expect("prefix has a dash") + 1..];
+        }
+
+        let code = match rest.to_ascii_lowercase().as_str() {
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key `{token}`")),
+                }
+            }
+        };
+        // terminals send the shifted character without shift, see `From<KeyEvent>`
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                if !c.is_ascii_alphabetic() {
+                    return Err(format!("write `{token}` as the shifted character itself"));
+                }
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        Ok(Key { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::BackTab => write!(f, "shift-tab"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            code => write!(f, "{}", code.to_string().to_lowercase()),
+        }
+    }
+}
+
+/// Parses a key sequence such as `gg`, `ctrl-q` or `g t`.
+///
+/// Space separates keys, and a run of plain characters is one key each.
+fn parse_sequence(sequence: &str) -> Result<Vec<Key>, String> {
+    let mut keys = Vec::new();
+    for token in sequence.split_whitespace() {
+        match Key::parse(token) {
+            Ok(key) => keys.push(key),
+            // `gg`, `gT`
+            Err(_) if !token.contains('-') => keys.extend(token.chars().map(|c| Key {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE,
+            })),
+            Err(err) => return Err(err),
+        }
+    }
+    if keys.is_empty() {
+        return Err(format!("empty key sequence `{sequence}`"));
+    }
+    Ok(keys)
+}
+
+pub enum KeyResult {
+    Action(Action),
+    // the keys so far are the start of a longer binding
+    Pending,
+    Unbound,
+}
+
+/// Key sequences bound to actions for a single mode.
+pub struct Keymap {
+    bindings: Vec<(Vec<Key>, Action)>,
+    pending: Vec<Key>,
+}
+
+impl Keymap {
+    fn new(defaults: &[(&str, Action)]) -> Self {
+        let bindings = defaults
+            .iter()
+            .map(|(sequence, action)| {
+                (
+                    parse_sequence(sequence).expect("default bindings are valid"),
+                    *action,
+                )
+            })
+            .collect();
+        Self {
+            bindings,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Replaces every binding of `action` with the comma separated `sequences`.
+    /// Nothing changes if any of them doesn't parse.
+    fn rebind(&mut self, action: Action, sequences: &str) -> Result<(), String> {
+        let sequences = sequences
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|sequence| parse_sequence(sequence.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.bindings.retain(|(_, bound)| *bound != action);
+        for keys in sequences {
+            self.bindings.retain(|(bound_keys, _)| *bound_keys != keys);
+            self.bindings.push((keys, action));
+        }
+        Ok(())
+    }
+
+    /// Feeds one key press, waiting for more keys if it starts a longer sequence.
+    pub fn feed(&mut self, event: KeyEvent) -> KeyResult {
+        self.pending.push(event.into());
+
+        if let Some((_, action)) = self.bindings.iter().find(|(keys, _)| *keys == self.pending) {
+            self.pending.clear();
+            return KeyResult::Action(*action);
+        }
+        if self
+            .bindings
+            .iter()
+            .any(|(keys, _)| keys.starts_with(&self.pending))
+        {
+            return KeyResult::Pending;
+        }
+
+        // a wrong second key starts over, so it can still begin a new sequence
+        let retry = self.pending.len() > 1;
+        self.pending.clear();
+        if retry {
+            self.feed(event)
+        } else {
+            KeyResult::Unbound
+        }
+    }
+
+    /// The first sequence bound to `action`, for hints in the interface.
+    pub fn keys_for(&self, action: Action) -> Option<String> {
+        self.describe()
+            .into_iter()
+            .find(|(_, bound)| *bound == action)
+            .map(|(keys, _)| keys.split(", ").next().unwrap_or_default().to_string())
+    }
+
+    /// `keys_for` as shown in hints, where an action without keys still needs a word.
+    pub fn hint(&self, action: Action) -> String {
+        self.keys_for(action)
+            .unwrap_or_else(|| "unbound".to_string())
+    }
+
+    /// Every binding, grouped by action in the order they were defined.
+    pub fn describe(&self) -> Vec<(String, Action)> {
+        let mut described: Vec<(String, Action)> = Vec::new();
+        for (keys, action) in &self.bindings {
+            // `gg` reads better than `g g`
+            let plain = keys
+                .iter()
+                .all(|k| matches!(k.code, KeyCode::Char(c) if c != ' ') && k.modifiers.is_empty());
+            let keys = keys
+                .iter()
+                .map(|k| k.to_string())
+                .collect::<Vec<String>>()
+                .join(if plain { "" } else { " " });
+            match described.iter_mut().find(|(_, a)| a == action) {
+                Some((existing, _)) => {
+                    existing.push_str(", ");
+                    existing.push_str(&keys);
+                }
+                None => described.push((keys, *action)),
+            }
+        }
+        described
+    }
+}
+
+/// The keymap of every mode that isn't typing text.
+///
+/// The chat input, reverse history search, the join dialog and buffer search
+/// type every key they don't use, so their few keys are fixed and only listed in help.
+pub struct Keymaps {
+    pub normal: Keymap,
+    pub search: Keymap,
+    pub moderate: Keymap,
+    // typing filters the list, so only keys that don't type anything are bound
+    pub chatters: Keymap,
+    pub user_card: Keymap,
+    // leaving a channel and banning someone
+    pub confirm: Keymap,
+    pub thread: Keymap,
+    pub filters: Keymap,
+    pub target: Keymap,
+    // any key that isn't bound closes the help
+    pub help: Keymap,
+}
+
+impl Default for Keymaps {
+    fn default() -> Self {
+        Self {
+            normal: Keymap::new(&[
+                ("ctrl-q", Action::Quit),
+                ("ctrl-c", Action::Quit),
+                ("x", Action::LeaveChannel),
+                ("u", Action::ReopenChannel),
+                ("\\", Action::OpenJoin),
+                ("k", Action::ScrollUp),
+                ("up", Action::ScrollUp),
+                ("j", Action::ScrollDown),
+                ("down", Action::ScrollDown),
+                ("gg", Action::ScrollTop),
+                ("G", Action::ScrollBottom),
+                ("tab", Action::NextChannel),
+                ("gt", Action::NextChannel),
+                ("shift-tab", Action::PreviousChannel),
+                ("gT", Action::PreviousChannel),
+                ("i", Action::StartEditing),
+                ("/", Action::StartBufferSearch),
+                ("n", Action::NextMatch),
+                ("N", Action::PreviousMatch),
+                ("esc", Action::ClearSearch),
+                ("?", Action::ToggleHelp),
+                ("m", Action::ToggleMouse),
+                ("M", Action::Moderate),
+                ("c", Action::ToggleChatters),
+                ("s", Action::ToggleStats),
+                ("ga", Action::OpenAllChannels),
+                ("gm", Action::OpenMentions),
+                ("gh", Action::OpenHighlights),
+                ("gf", Action::OpenFirstChatters),
+                ("ctrl-w v", Action::SplitVertical),
+                ("ctrl-w s", Action::SplitHorizontal),
+                ("ctrl-w w", Action::NextPane),
+                ("ctrl-w p", Action::PreviousPane),
+                ("ctrl-w q", Action::ClosePane),
+                ("ctrl-w >", Action::GrowPane),
+                ("ctrl-w <", Action::ShrinkPane),
+                ("ctrl-w S", Action::SaveLayout),
+            ]),
+            search: Keymap::new(&[
+                ("j", Action::SelectNext),
+                ("down", Action::SelectNext),
+                ("k", Action::SelectPrevious),
+                ("up", Action::SelectPrevious),
+                ("enter", Action::Confirm),
+                ("esc", Action::Cancel),
+            ]),
+            moderate: Keymap::new(&[
+                ("k", Action::SelectPrevious),
+                ("up", Action::SelectPrevious),
+                ("j", Action::SelectNext),
+                ("down", Action::SelectNext),
+                ("d", Action::DeleteMessage),
+                ("1", Action::TimeoutMinute),
+                ("2", Action::TimeoutTenMinutes),
+                ("3", Action::TimeoutHour),
+                ("4", Action::TimeoutDay),
+                ("b", Action::BanAuthor),
+                ("y", Action::CopyName),
+                ("r", Action::Reply),
+                ("t", Action::ShowThread),
+                ("x", Action::ToggleRepeats),
+                ("esc", Action::Cancel),
+            ]),
+            chatters: Keymap::new(&[
+                ("down", Action::SelectNext),
+                ("up", Action::SelectPrevious),
+                ("enter", Action::Confirm),
+                ("esc", Action::Cancel),
+            ]),
+            user_card: Keymap::new(&[
+                ("k", Action::ScrollUp),
+                ("up", Action::ScrollUp),
+                ("j", Action::ScrollDown),
+                ("down", Action::ScrollDown),
+                ("s", Action::ToggleHistory),
+                ("m", Action::MentionUser),
+                ("w", Action::WhisperUser),
+                ("i", Action::IgnoreUser),
+                ("t", Action::TimeoutUser),
+                ("b", Action::BanUser),
+                ("esc", Action::Cancel),
+                ("q", Action::Cancel),
+            ]),
+            confirm: Keymap::new(&[
+                ("y", Action::Confirm),
+                ("enter", Action::Confirm),
+                ("n", Action::Cancel),
+                ("esc", Action::Cancel),
+            ]),
+            thread: Keymap::new(&[("esc", Action::Cancel), ("q", Action::Cancel)]),
+            filters: Keymap::new(&[("esc", Action::Cancel), ("q", Action::Cancel)]),
+            target: Keymap::new(&[
+                ("j", Action::SelectNext),
+                ("down", Action::SelectNext),
+                ("k", Action::SelectPrevious),
+                ("up", Action::SelectPrevious),
+                ("enter", Action::Confirm),
+                ("esc", Action::Cancel),
+            ]),
+            help: Keymap::new(&[
+                ("k", Action::ScrollUp),
+                ("up", Action::ScrollUp),
+                ("j", Action::ScrollDown),
+                ("down", Action::ScrollDown),
+            ]),
+        }
+    }
+}
+
+impl Keymaps {
+    /// Default keymaps with `(mode, action, keys)` overrides from the config applied.
+    ///
+    /// An override that can't be applied is skipped, and comes back as an error message.
+    pub fn with_overrides(overrides: &[(String, String, String)]) -> (Self, Vec<String>) {
+        let mut keymaps = Keymaps::default();
+        let errors = overrides
+            .iter()
+            .filter_map(|(mode, action, sequences)| keymaps.rebind(mode, action, sequences).err())
+            .collect();
+        (keymaps, errors)
+    }
+
+    fn rebind(&mut self, mode: &str, action_name: &str, sequences: &str) -> Result<(), String> {
+        let action = Action::from_name(action_name)
+            .ok_or_else(|| format!("unknown action `{action_name}` in keymap.{mode}"))?;
+        let keymap = match mode {
+            "normal" => &mut self.normal,
+            "search" => &mut self.search,
+            "moderate" => &mut self.moderate,
+            "chatters" => &mut self.chatters,
+            "user_card" => &mut self.user_card,
+            "confirm" => &mut self.confirm,
+            "thread" => &mut self.thread,
+            "filters" => &mut self.filters,
+            "target" => &mut self.target,
+            "help" => &mut self.help,
+            _ => return Err(format!("unknown keymap mode `{mode}`")),
+        };
+        keymap
+            .rebind(action, sequences)
+            .map_err(|err| format!("keymap.{mode}.{action_name}: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(keymap: &mut Keymap, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        match keymap.feed(KeyEvent::new(code, modifiers)) {
+            KeyResult::Action(action) => Some(action),
+            KeyResult::Pending | KeyResult::Unbound => None,
+        }
+    }
+
+    fn overrides(mode: &str, action: &str, keys: &str) -> Vec<(String, String, String)> {
+        vec![(mode.to_string(), action.to_string(), keys.to_string())]
+    }
+
+    // the keymaps with a single override, which has to apply
+    fn with_override(mode: &str, action: &str, keys: &str) -> Keymaps {
+        let (keymaps, errors) = Keymaps::with_overrides(&overrides(mode, action, keys));
+        assert_eq!(errors, Vec::<String>::new());
+        keymaps
+    }
+
+    // the error a single override gives
+    fn override_error(mode: &str, action: &str, keys: &str) -> Option<String> {
+        Keymaps::with_overrides(&overrides(mode, action, keys))
+            .1
+            .pop()
+    }
+
+    #[test]
+    fn shifted_letters_match_what_the_terminal_sends() {
+        let mut keymaps = with_override("normal", "scroll_top", "shift-t");
+        let keymap = &mut keymaps.normal;
+        assert_eq!(
+            press(keymap, KeyCode::Char('T'), KeyModifiers::SHIFT),
+            Some(Action::ScrollTop)
+        );
+        assert_eq!(keymap.keys_for(Action::ScrollTop).as_deref(), Some("T"));
+    }
+
+    #[test]
+    fn shifted_symbols_are_rejected() {
+        let err = override_error("normal", "quit", "shift-1").unwrap();
+        assert!(err.contains("shifted character"), "{err}");
+    }
+
+    #[test]
+    fn rebinding_replaces_the_defaults() {
+        let mut keymaps = with_override("confirm", "confirm", "o");
+        let keymap = &mut keymaps.confirm;
+        assert_eq!(press(keymap, KeyCode::Char('y'), KeyModifiers::NONE), None);
+        assert_eq!(
+            press(keymap, KeyCode::Char('o'), KeyModifiers::NONE),
+            Some(Action::Confirm)
+        );
+        assert_eq!(
+            press(keymap, KeyCode::Char('n'), KeyModifiers::NONE),
+            Some(Action::Cancel)
+        );
+    }
+
+    #[test]
+    fn sequences_wait_for_their_last_key() {
+        let mut keymaps = with_override("user_card", "ban", "g b");
+        let keymap = &mut keymaps.user_card;
+        assert_eq!(press(keymap, KeyCode::Char('g'), KeyModifiers::NONE), None);
+        assert_eq!(
+            press(keymap, KeyCode::Char('b'), KeyModifiers::NONE),
+            Some(Action::BanUser)
+        );
+        assert_eq!(keymap.keys_for(Action::BanUser).as_deref(), Some("gb"));
+    }
+
+    #[test]
+    fn every_mode_can_be_overridden() {
+        for mode in [
+            "normal",
+            "search",
+            "moderate",
+            "chatters",
+            "user_card",
+            "confirm",
+            "thread",
+            "filters",
+            "target",
+            "help",
+        ] {
+            assert_eq!(override_error(mode, "cancel", "x"), None, "{mode}");
+        }
+        assert!(override_error("nowhere", "cancel", "x").is_some());
+    }
+
+    #[test]
+    fn bad_overrides_are_skipped_and_leave_the_defaults() {
+        let overrides = [
+            ("normal", "quit", "shift-1"),
+            ("normal", "no_such_action", "x"),
+            ("confirm", "confirm", "o"),
+        ]
+        .map(|(mode, action, keys)| (mode.to_string(), action.to_string(), keys.to_string()));
+        let (mut keymaps, errors) = Keymaps::with_overrides(&overrides);
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert_eq!(
+            keymaps.normal.keys_for(Action::Quit),
+            Keymaps::default().normal.keys_for(Action::Quit)
+        );
+        assert_eq!(
+            press(&mut keymaps.confirm, KeyCode::Char('o'), KeyModifiers::NONE),
+            Some(Action::Confirm)
+        );
+    }
+}
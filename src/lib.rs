@@ -0,0 +1,24 @@
+pub mod app;
+pub mod chatters;
+pub mod clipboard;
+pub mod completion;
+pub mod components;
+pub mod event;
+pub mod filters;
+pub mod history;
+pub mod irc_handler;
+pub mod join_input;
+pub mod key_handler;
+pub mod keymap;
+pub mod logger;
+pub mod messagebox;
+pub mod mouse_handler;
+pub mod panes;
+pub mod search;
+pub mod settings;
+pub mod stats;
+pub mod store;
+pub mod text_input;
+pub mod tui;
+pub mod twitch;
+pub mod ui;
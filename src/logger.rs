@@ -0,0 +1,290 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    settings::{LogRotation, Settings},
+    twitch::client_stream::{message_time, ClientEvent, Tags},
+};
+
+/// One line of the structured chat log.
+#[derive(Serialize)]
+struct LogRecord<'a> {
+    timestamp: String,
+    channel: &'a str,
+    kind: &'a str,
+    nickname: Option<&'a str>,
+    message: Option<&'a str>,
+    tags: &'a Tags,
+}
+
+// files currently open for a single channel
+struct ChannelLog {
+    date: NaiveDate,
+    text: Option<File>,
+    jsonl: Option<File>,
+}
+
+/// Writes chat traffic to disk, one directory per channel.
+///
+/// With daily rotation each day gets its own `YYYY-MM-DD.log` (plain text)
+/// and `YYYY-MM-DD.jsonl` (one json object per line, including tags).
+pub struct ChatLogger {
+    settings: Settings,
+    open_logs: HashMap<String, ChannelLog>,
+}
+
+impl ChatLogger {
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            settings,
+            open_logs: HashMap::new(),
+        }
+    }
+
+    /// Logs anything from the irc event stream that is worth keeping.
+    pub fn log_event(&mut self, event: &ClientEvent) -> io::Result<()> {
+        match event {
+            ClientEvent::Privmsg(channel, msg, nickname, tags) => {
+                self.log_message(channel, nickname.as_deref(), msg, tags)
+            }
+            ClientEvent::Join(channel, nickname) if self.settings.log_membership => {
+                self.write(channel, "join", Some(nickname), None, &Tags::new())
+            }
+            ClientEvent::Leave(channel, nickname) if self.settings.log_membership => {
+                self.write(channel, "part", Some(nickname), None, &Tags::new())
+            }
+            ClientEvent::Whisper(nickname, msg, tags) => {
+                self.log_message(&format!("@{nickname}"), Some(nickname), msg, tags)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn log_message(
+        &mut self,
+        channel: &str,
+        nickname: Option<&str>,
+        content: &str,
+        tags: &Tags,
+    ) -> io::Result<()> {
+        self.write(channel, "privmsg", nickname, Some(content), tags)
+    }
+
+    fn write(
+        &mut self,
+        channel: &str,
+        kind: &str,
+        nickname: Option<&str>,
+        message: Option<&str>,
+        tags: &Tags,
+    ) -> io::Result<()> {
+        if !self.settings.should_log(channel) {
+            return Ok(());
+        }
+
+        let timestamp = message_time(tags);
+        let log = self.open(channel, timestamp.date_naive())?;
+
+        if let Some(file) = log.text.as_mut() {
+            let time = timestamp.format("%H:%M:%S");
+            let line = match (kind, nickname, message) {
+                ("privmsg", Some(nick), Some(msg)) => format!("[{time}] <{nick}> {msg}"),
+                ("privmsg", None, Some(msg)) => format!("[{time}] {msg}"),
+                (kind, Some(nick), None) => format!("[{time}] -- {kind} {channel} {nick}"),
+                (kind, _, _) => format!("[{time}] -- {kind} {channel}"),
+            };
+            writeln!(file, "{line}")?;
+        }
+
+        if let Some(file) = log.jsonl.as_mut() {
+            let record = LogRecord {
+                timestamp: timestamp.to_rfc3339(),
+                channel,
+                kind,
+                nickname,
+                message,
+                tags,
+            };
+            let line = serde_json::to_string(&record).map_err(io::Error::other)?;
+            writeln!(file, "{line}")?;
+        }
+
+        Ok(())
+    }
+
+    // returns the open files for a channel, rolling over to a new day if needed
+    fn open(&mut self, channel: &str, date: NaiveDate) -> io::Result<&mut ChannelLog> {
+        let rotation = self.settings.log_rotation;
+        let needs_open = match self.open_logs.get(channel) {
+            Some(log) => rotation == LogRotation::Daily && log.date != date,
+            None => true,
+        };
+
+        if needs_open {
+            let dir = self
+                .settings
+                .log_dir()
+                .join(channel.trim_start_matches('#'));
+            fs::create_dir_all(&dir)?;
+            prune_old_logs(&dir, self.settings.log_retention_days)?;
+
+            let stem = match rotation {
+                LogRotation::Daily => date.format("%Y-%m-%d").to_string(),
+                LogRotation::Never => "chat".to_string(),
+            };
+            let text = if self.settings.log_text {
+                Some(append(&dir.join(format!("{stem}.log")))?)
+            } else {
+                None
+            };
+            let jsonl = if self.settings.log_jsonl {
+                Some(append(&dir.join(format!("{stem}.jsonl")))?)
+            } else {
+                None
+            };
+
+            self.open_logs
+                .insert(channel.to_string(), ChannelLog { date, text, jsonl });
+        }
+
+        Ok(self
+            .open_logs
+            .get_mut(channel)
+            .expect("log was just opened"))
+    }
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn prune_old_logs(dir: &Path, retention_days: u64) -> io::Result<()> {
+    if retention_days == 0 {
+        return Ok(());
+    }
+
+    let max_age = Duration::from_secs(retention_days * 24 * 60 * 60);
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        // only files we wrote, anything else in the directory is left alone
+        let ours = entry
+            .path()
+            .extension()
+            .is_some_and(|ext| ext == "log" || ext == "jsonl");
+        if !ours {
+            continue;
+        }
+        let modified = entry.metadata()?.modified()?;
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+        if age > max_age {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+
+    // a logger writing under a fresh directory in the system temp dir
+    fn logger(name: &str) -> (ChatLogger, PathBuf) {
+        let data_dir =
+            std::env::temp_dir().join(format!("twi-rs-logger-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&data_dir);
+        let settings = Settings {
+            data_dir: data_dir.clone(),
+            ..Default::default()
+        };
+        (
+            ChatLogger::new(settings),
+            data_dir.join("logs").join("chan"),
+        )
+    }
+
+    fn sent_at(day: u32, hour: u32, minute: u32) -> Tags {
+        let time = Local
+            .with_ymd_and_hms(2024, 3, day, hour, minute, 0)
+            .unwrap();
+        Tags::from([(
+            "tmi-sent-ts".to_string(),
+            time.timestamp_millis().to_string(),
+        )])
+    }
+
+    fn lines(path: PathBuf) -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn each_day_gets_its_own_files() {
+        let (mut logger, dir) = logger("daily");
+        logger
+            .log_message("#chan", Some("alice"), "late", &sent_at(1, 23, 59))
+            .unwrap();
+        logger
+            .log_message("#chan", Some("bob"), "early", &sent_at(2, 0, 1))
+            .unwrap();
+        logger
+            .log_message("#chan", None, "no sender", &sent_at(2, 0, 2))
+            .unwrap();
+
+        assert_eq!(
+            lines(dir.join("2024-03-01.log")),
+            ["[23:59:00] <alice> late"]
+        );
+        assert_eq!(
+            lines(dir.join("2024-03-02.log")),
+            ["[00:01:00] <bob> early", "[00:02:00] no sender"]
+        );
+
+        let records = lines(dir.join("2024-03-02.jsonl"));
+        assert_eq!(records.len(), 2);
+        let record: serde_json::Value = serde_json::from_str(&records[0]).unwrap();
+        assert_eq!(record["channel"], "#chan");
+        assert_eq!(record["kind"], "privmsg");
+        assert_eq!(record["nickname"], "bob");
+        assert_eq!(record["message"], "early");
+    }
+
+    #[test]
+    fn without_rotation_everything_goes_in_one_file() {
+        let (mut logger, dir) = logger("never");
+        logger.settings.log_rotation = LogRotation::Never;
+        logger.settings.log_membership = true;
+        logger
+            .log_message("#chan", Some("alice"), "late", &sent_at(1, 23, 59))
+            .unwrap();
+        logger
+            .log_event(&ClientEvent::Join("#chan".to_string(), "bob".to_string()))
+            .unwrap();
+        logger
+            .log_message("#chan", Some("bob"), "early", &sent_at(2, 0, 1))
+            .unwrap();
+
+        let lines = lines(dir.join("chat.log"));
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "[23:59:00] <alice> late");
+        assert!(lines[1].ends_with("] -- join #chan bob"), "{}", lines[1]);
+        assert_eq!(lines[2], "[00:01:00] <bob> early");
+        assert!(!dir.join("2024-03-02.log").exists());
+    }
+}
@@ -1,25 +1,22 @@
 use dotenv::dotenv;
-use event::{Event, EventHandler};
-
-use irc_handler::handle_irc_messages;
-use key_handler::handle_key_events;
-use ratatui::{backend::CrosstermBackend, Terminal};
+use irc::client::data::Config;
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    Terminal,
+};
 use std::io;
 use tokio_util::sync::CancellationToken;
-use tui::Tui;
-
-mod app;
-mod components;
-mod event;
-mod irc_handler;
-mod join_input;
-mod key_handler;
-mod messagebox;
-mod tui;
-mod twitch;
-mod ui;
 
-use crate::app::{App, AppResult};
+use twi_rs::{
+    app::{App, AppResult},
+    event::{Event, EventHandler},
+    irc_handler::handle_irc_messages,
+    key_handler::handle_key_events,
+    mouse_handler::handle_mouse_events,
+    settings::{Settings, CONFIG_PATH},
+    tui::Tui,
+    twitch::{client_stream::create_client_stream, helix::HelixClient},
+};
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
@@ -30,31 +27,56 @@ async fn main() -> AppResult<()> {
     //clone cancel token to pass to events handler
     let cloned_cancel_token = cancel_token.clone();
 
+    let config = Config::load(CONFIG_PATH)?;
+    let settings = Settings::from_options(&config.options);
+    let helix = match (&settings.client_id, &config.password) {
+        (Some(client_id), Some(token)) => {
+            Some(HelixClient::new(&settings.helix_url, client_id, token))
+        }
+        _ => None,
+    };
+
     // create irc client and stream
-    let (client, client_stream) = twitch::client_stream::create_client_stream().await?;
-    let mut app = App::new(client, cancel_token);
+    let (client, client_stream) = create_client_stream(config.clone(), None).await?;
+    let mut app = App::new(client, settings, cancel_token)?;
 
     // init terminal ui
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new(client_stream, cloned_cancel_token);
+    let events = EventHandler::new(config, client_stream, cloned_cancel_token);
+
+    if let Some(helix) = helix {
+        app.connect_helix(helix, events.sender());
+    }
 
     let mut tui = Tui::new(terminal, events);
 
     tui.init()?;
+    // the terminal is restored whatever ended the loop
+    let result = run(&mut app, &mut tui).await;
+    tui.exit()?;
+    result
+}
+
+async fn run<B: Backend>(app: &mut App, tui: &mut Tui<B>) -> AppResult<()> {
+    tui.set_mouse_capture(app.mouse_capture)?;
 
     while app.running {
-        tui.draw(&mut app)?;
+        tui.draw(app)?;
 
         if let Some(event) = tui.events.next().await {
             match event {
-                Event::Client(irc_event) => handle_irc_messages(irc_event, &mut app)?,
-                Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
+                Event::Client(irc_event) => handle_irc_messages(irc_event, app)?,
+                Event::Reconnected(client) => app.reconnected(*client),
+                Event::Disconnected => app.disconnected(),
+                Event::ReconnectFailed { error, retry_in } => app.reconnect_failed(error, retry_in),
+                Event::Helix(helix_event) => app.on_helix_event(helix_event),
+                Event::Key(key_event) => handle_key_events(key_event, app)?,
+                Event::Mouse(mouse_event) => handle_mouse_events(mouse_event, app)?,
                 Event::Resize => tui.resize()?,
             }
         }
+        tui.set_mouse_capture(app.mouse_capture)?;
     }
-
-    tui.exit()?;
     Ok(())
 }
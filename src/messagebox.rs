@@ -1,74 +1,46 @@
+use crate::text_input::TextInput;
+
+// twitch drops anything longer than this
+pub const MAX_MESSAGE_LENGTH: usize = 500;
+// the counter turns yellow this close to the limit
+const LENGTH_WARNING: usize = 50;
+
 pub enum MessageMode {
     Normal,
     Editing,
+    // ctrl-r reverse search through sent messages
+    HistorySearch,
 }
 
 pub struct MessageBox {
     pub mode: MessageMode,
-    pub input: String,
-    pub character_index: usize,
+    pub input: TextInput,
 }
 
 impl Default for MessageBox {
     fn default() -> Self {
         Self {
             mode: MessageMode::Normal,
-            input: String::new(),
-            character_index: 0,
+            input: TextInput::default(),
         }
     }
 }
 
 impl MessageBox {
-    pub fn move_cursor_left(&mut self) {
-        let cursor_moved_left = self.character_index.saturating_sub(1);
-        self.character_index = self.clamp_cursor(cursor_moved_left);
-    }
-
-    pub fn move_cursor_right(&mut self) {
-        let cursor_moved_right = self.character_index.saturating_add(1);
-        self.character_index = self.clamp_cursor(cursor_moved_right);
-    }
-
-    pub fn enter_char(&mut self, new_char: char) {
-        let index = self.byte_index();
-        self.input.insert(index, new_char);
-        self.move_cursor_right();
+    /// Replaces the input, leaving the cursor at the end.
+    pub fn set_input(&mut self, input: String) {
+        self.input.set_text(input);
     }
 
-    fn byte_index(&self) -> usize {
-        self.input
-            .char_indices()
-            .map(|(i, _)| i)
-            .nth(self.character_index)
-            .unwrap_or(self.input.len())
+    pub fn is_too_long(&self) -> bool {
+        self.input.char_count() > MAX_MESSAGE_LENGTH
     }
 
-    pub fn delete_char(&mut self) {
-        let is_not_cursor_leftmost = self.character_index != 0;
-        if is_not_cursor_leftmost {
-            let current_index = self.character_index;
-            let from_left_to_current_index = current_index - 1;
-
-            let before_char_to_delete = self.input.chars().take(from_left_to_current_index);
-            let after_char_to_delete = self.input.chars().skip(current_index);
-
-            // Put all characters together except the selected one.
-            // By leaving the selected one out, it is forgotten and therefore deleted.
-            self.input = before_char_to_delete.chain(after_char_to_delete).collect();
-            self.move_cursor_left();
-        }
-    }
-
-    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.input.chars().count())
+    pub fn is_near_limit(&self) -> bool {
+        self.input.char_count() + LENGTH_WARNING > MAX_MESSAGE_LENGTH
     }
 
     pub fn clear_box(&mut self) {
         self.input.clear();
-        self.reset_cursor();
-    }
-    pub fn reset_cursor(&mut self) {
-        self.character_index = 0;
     }
 }
@@ -0,0 +1,63 @@
+use crate::app::{App, AppMode, AppResult};
+use ratatui::{
+    crossterm::event::{MouseButton, MouseEvent, MouseEventKind},
+    layout::{Position, Rect},
+};
+
+// lines moved per wheel notch
+const SCROLL_LINES: usize = 3;
+
+fn contains(area: Rect, event: &MouseEvent) -> bool {
+    area.contains(Position::new(event.column, event.row))
+}
+
+pub fn handle_mouse_events(mouse_event: MouseEvent, app: &mut App) -> AppResult<()> {
+    // overlays and prompts keep the keyboard focus, ignore clicks behind them
+    if !matches!(app.app_mode, AppMode::Normal) {
+        return Ok(());
+    }
+
+    match mouse_event.kind {
+        MouseEventKind::ScrollUp if contains(app.click_areas.messages, &mouse_event) => {
+            let offset = app.list_state.offset();
+            *app.list_state.offset_mut() = offset.saturating_add(SCROLL_LINES);
+        }
+        MouseEventKind::ScrollDown if contains(app.click_areas.messages, &mouse_event) => {
+            let offset = app.list_state.offset();
+            *app.list_state.offset_mut() = offset.saturating_sub(SCROLL_LINES);
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            let clicked_tab = app
+                .click_areas
+                .tabs
+                .iter()
+                .position(|area| contains(*area, &mouse_event));
+            if let Some(index) = clicked_tab {
+                app.current_channel = index;
+                return Ok(());
+            }
+
+            let clicked_nickname = app
+                .click_areas
+                .nicknames
+                .iter()
+                .find(|(area, _)| contains(*area, &mouse_event))
+                .map(|(_, nickname)| nickname.clone());
+            if let Some(nickname) = clicked_nickname {
+                app.open_user_card(nickname);
+                return Ok(());
+            }
+
+            let clicked_pane = app
+                .click_areas
+                .panes
+                .iter()
+                .position(|area| contains(*area, &mouse_event));
+            if let Some(index) = clicked_pane {
+                app.focus_pane(index);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,356 @@
+use std::fmt;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::ListState,
+};
+
+// a pane never shrinks below this share of its split
+const MIN_RATIO: u16 = 10;
+
+/// A messages view bound to one channel.
+#[derive(Default, Clone)]
+pub struct Pane {
+    pub channel: String,
+    pub list_state: ListState,
+}
+
+enum PaneNode {
+    Leaf(Pane),
+    Split {
+        // `Horizontal` puts the two halves side by side
+        direction: Direction,
+        // percentage of the space given to `first`
+        ratio: u16,
+        first: Box<PaneNode>,
+        second: Box<PaneNode>,
+    },
+}
+
+impl PaneNode {
+    fn leaf_count(&self) -> usize {
+        match self {
+            PaneNode::Leaf(_) => 1,
+            PaneNode::Split { first, second, .. } => first.leaf_count() + second.leaf_count(),
+        }
+    }
+
+    fn leaves<'a>(&'a self, found: &mut Vec<&'a Pane>) {
+        match self {
+            PaneNode::Leaf(pane) => found.push(pane),
+            PaneNode::Split { first, second, .. } => {
+                first.leaves(found);
+                second.leaves(found);
+            }
+        }
+    }
+
+    fn leaf_mut(&mut self, index: usize) -> Option<&mut Pane> {
+        match self {
+            PaneNode::Leaf(pane) => (index == 0).then_some(pane),
+            PaneNode::Split { first, second, .. } => {
+                let first_count = first.leaf_count();
+                if index < first_count {
+                    first.leaf_mut(index)
+                } else {
+                    second.leaf_mut(index - first_count)
+                }
+            }
+        }
+    }
+
+    fn areas(&self, area: Rect, found: &mut Vec<Rect>) {
+        match self {
+            PaneNode::Leaf(_) => found.push(area),
+            PaneNode::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let [first_area, second_area] = Layout::default()
+                    .direction(*direction)
+                    .constraints([Constraint::Percentage(*ratio), Constraint::Fill(1)])
+                    .areas(area);
+                first.areas(first_area, found);
+                second.areas(second_area, found);
+            }
+        }
+    }
+
+    fn split(&mut self, index: usize, direction: Direction, pane: Pane) {
+        match self {
+            PaneNode::Leaf(_) => {
+                let existing = std::mem::replace(self, PaneNode::Leaf(Pane::default()));
+                *self = PaneNode::Split {
+                    direction,
+                    ratio: 50,
+                    first: Box::new(existing),
+                    second: Box::new(PaneNode::Leaf(pane)),
+                };
+            }
+            PaneNode::Split { first, second, .. } => {
+                let first_count = first.leaf_count();
+                if index < first_count {
+                    first.split(index, direction, pane);
+                } else {
+                    second.split(index - first_count, direction, pane);
+                }
+            }
+        }
+    }
+
+    // replaces the split holding the leaf with the leaf's sibling
+    fn remove(&mut self, index: usize) {
+        let PaneNode::Split { first, second, .. } = self else {
+            return;
+        };
+        let first_count = first.leaf_count();
+        let sibling = match (index, first_count, second.leaf_count()) {
+            (0, 1, _) => std::mem::replace(second.as_mut(), PaneNode::Leaf(Pane::default())),
+            (index, first_count, 1) if index == first_count => {
+                std::mem::replace(first.as_mut(), PaneNode::Leaf(Pane::default()))
+            }
+            (index, first_count, _) if index < first_count => return first.remove(index),
+            (index, first_count, _) => return second.remove(index - first_count),
+        };
+        *self = sibling;
+    }
+
+    // grows the leaf within the innermost split holding it, returns false for a lone leaf
+    fn resize(&mut self, index: usize, delta: i16) -> bool {
+        let PaneNode::Split {
+            ratio,
+            first,
+            second,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        let first_count = first.leaf_count();
+        let (resized, delta) = if index < first_count {
+            (first.resize(index, delta), delta)
+        } else {
+            (second.resize(index - first_count, delta), -delta)
+        };
+        if !resized {
+            *ratio = (*ratio as i16 + delta).clamp(MIN_RATIO as i16, 100 - MIN_RATIO as i16) as u16;
+        }
+        true
+    }
+}
+
+// a pane that isn't showing a channel yet
+const EMPTY_PANE: &str = "-";
+
+// `rows(50,#a,cols(30,#b,-))`
+impl fmt::Display for PaneNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaneNode::Leaf(pane) if pane.channel.is_empty() => write!(f, "{EMPTY_PANE}"),
+            PaneNode::Leaf(pane) => write!(f, "{}", pane.channel),
+            PaneNode::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let name = match direction {
+                    Direction::Horizontal => "cols",
+                    Direction::Vertical => "rows",
+                };
+                write!(f, "{name}({ratio},{first},{second})")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    fn expect(&mut self, token: char) -> Result<(), String> {
+        self.rest = self.rest.trim_start();
+        self.rest = self
+            .rest
+            .strip_prefix(token)
+            .ok_or_else(|| format!("expected `{token}` in layout at `{}`", self.rest))?;
+        Ok(())
+    }
+
+    fn word(&mut self) -> &str {
+        self.rest = self.rest.trim_start();
+        let end = self.rest.find([',', '(', ')']).unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        word.trim()
+    }
+
+    fn node(&mut self) -> Result<PaneNode, String> {
+        let word = self.word().to_string();
+        let direction = match word.as_str() {
+            "cols" => Direction::Horizontal,
+            "rows" => Direction::Vertical,
+            "" => return Err("missing pane in layout".to_string()),
+            EMPTY_PANE => return Ok(PaneNode::Leaf(Pane::default())),
+            // virtual tabs such as `[all]` and whisper tabs such as `@nick` keep their name
+            channel if channel.starts_with(['[', '@']) => {
+                return Ok(PaneNode::Leaf(Pane {
+                    channel: channel.to_string(),
+                    list_state: ListState::default(),
+                }))
+            }
+            channel => {
+                return Ok(PaneNode::Leaf(Pane {
+                    channel: crate::settings::normalize_channel(channel),
+                    list_state: ListState::default(),
+                }))
+            }
+        };
+
+        self.expect('(')?;
+        let ratio: u16 = self
+            .word()
+            .parse()
+            .map_err(|_| format!("bad ratio in layout `{word}(...)`"))?;
+        self.expect(',')?;
+        let first = self.node()?;
+        self.expect(',')?;
+        let second = self.node()?;
+        self.expect(')')?;
+        Ok(PaneNode::Split {
+            direction,
+            ratio: ratio.clamp(MIN_RATIO, 100 - MIN_RATIO),
+            first: Box::new(first),
+            second: Box::new(second),
+        })
+    }
+}
+
+/// Panes the messages area is split into, as a tree of splits.
+///
+/// Panes are numbered in reading order, left to right and top to bottom.
+pub struct PaneLayout {
+    root: PaneNode,
+    pub focused: usize,
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self {
+            root: PaneNode::Leaf(Pane::default()),
+            focused: 0,
+        }
+    }
+}
+
+impl fmt::Display for PaneLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+impl PaneLayout {
+    /// Parses a saved layout such as `cols(50,#a,rows(50,#b,-))`, `-` is a pane without a channel.
+    pub fn parse(layout: &str) -> Result<Self, String> {
+        let mut parser = Parser { rest: layout };
+        let root = parser.node()?;
+        if !parser.rest.trim().is_empty() {
+            return Err(format!("unexpected `{}` at the end of layout", parser.rest));
+        }
+        Ok(Self { root, focused: 0 })
+    }
+
+    pub fn count(&self) -> usize {
+        self.root.leaf_count()
+    }
+
+    pub fn panes(&self) -> Vec<&Pane> {
+        let mut found = Vec::new();
+        self.root.leaves(&mut found);
+        found
+    }
+
+    pub fn focused_pane(&self) -> &Pane {
+        self.panes()[self.focused]
+    }
+
+    pub fn focused_pane_mut(&mut self) -> &mut Pane {
+        self.root
+            .leaf_mut(self.focused)
+            .expect("focused pane exists")
+    }
+
+    pub fn pane_mut(&mut self, index: usize) -> Option<&mut Pane> {
+        self.root.leaf_mut(index)
+    }
+
+    /// Area of every pane within `area`, in pane order.
+    pub fn areas(&self, area: Rect) -> Vec<Rect> {
+        let mut found = Vec::new();
+        self.root.areas(area, &mut found);
+        found
+    }
+
+    /// Splits the focused pane in two, the new half shows `channel` and takes focus.
+    pub fn split(&mut self, direction: Direction, channel: String) {
+        let pane = Pane {
+            channel,
+            list_state: ListState::default(),
+        };
+        self.root.split(self.focused, direction, pane);
+        self.focused += 1;
+    }
+
+    /// Closes the focused pane unless it is the last one.
+    pub fn close_focused(&mut self) -> bool {
+        if self.count() == 1 {
+            return false;
+        }
+        self.root.remove(self.focused);
+        self.focused = self.focused.min(self.count() - 1);
+        true
+    }
+
+    /// Grows the focused pane by `delta` percent of its split, negative shrinks it.
+    pub fn resize_focused(&mut self, delta: i16) {
+        self.root.resize(self.focused, delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_panes_survive_a_save() {
+        let mut layout = PaneLayout::default();
+        layout.split(Direction::Horizontal, String::new());
+        assert_eq!(layout.to_string(), "cols(50,-,-)");
+
+        let parsed = PaneLayout::parse(&layout.to_string()).unwrap();
+        assert_eq!(parsed.count(), 2);
+        assert!(parsed.panes().iter().all(|p| p.channel.is_empty()));
+    }
+
+    #[test]
+    fn channels_are_normalized() {
+        let layout = PaneLayout::parse("rows(30, Foo ,cols(50,#bar,-))").unwrap();
+        assert_eq!(layout.to_string(), "rows(30,#foo,cols(50,#bar,-))");
+    }
+
+    #[test]
+    fn tabs_that_are_not_channels_keep_their_name() {
+        let layout = PaneLayout::parse("cols(50,[all],@friend)").unwrap();
+        assert_eq!(layout.to_string(), "cols(50,[all],@friend)");
+    }
+
+    #[test]
+    fn missing_panes_are_rejected() {
+        assert!(PaneLayout::parse("").is_err());
+        assert!(PaneLayout::parse("cols(50,,#a)").is_err());
+        assert!(PaneLayout::parse("cols(50,#a)").is_err());
+    }
+}
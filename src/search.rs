@@ -0,0 +1,130 @@
+use std::ops::Range;
+
+use ratatui::widgets::ListState;
+use regex::{Regex, RegexBuilder};
+
+use crate::{app::MessageInfo, store::StoredMessage};
+
+/// State of the `/search` results overlay.
+#[derive(Default)]
+pub struct SearchOverlay {
+    pub query: String,
+    pub results: Vec<StoredMessage>,
+    pub list_state: ListState,
+    // surrounding messages for a result that is no longer in scrollback
+    pub context: Option<Vec<StoredMessage>>,
+}
+
+impl SearchOverlay {
+    pub fn show(&mut self, query: String, results: Vec<StoredMessage>) {
+        self.query = query;
+        self.list_state = ListState::default();
+        if !results.is_empty() {
+            self.list_state.select(Some(0));
+        }
+        self.results = results;
+        self.context = None;
+    }
+
+    pub fn selected(&self) -> Option<&StoredMessage> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.results.get(index))
+    }
+
+    pub fn select_next(&mut self) {
+        if self.context.is_none() {
+            self.list_state.select_next();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.context.is_none() {
+            self.list_state.select_previous();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = SearchOverlay::default();
+    }
+}
+
+/// Vim style `/` search over the current channel's scrollback.
+#[derive(Default)]
+pub struct BufferSearch {
+    pub pattern: String,
+    pub use_regex: bool,
+    pub case_insensitive: bool,
+    // compiled from `pattern`, `None` while empty or invalid
+    pub matcher: Option<Regex>,
+    pub error: Option<String>,
+}
+
+impl BufferSearch {
+    pub fn enter_char(&mut self, new_char: char) {
+        self.pattern.push(new_char);
+        self.compile();
+    }
+
+    pub fn delete_char(&mut self) {
+        self.pattern.pop();
+        self.compile();
+    }
+
+    pub fn toggle_regex(&mut self) {
+        self.use_regex = !self.use_regex;
+        self.compile();
+    }
+
+    pub fn toggle_case(&mut self) {
+        self.case_insensitive = !self.case_insensitive;
+        self.compile();
+    }
+
+    pub fn clear(&mut self) {
+        self.pattern.clear();
+        self.compile();
+    }
+
+    fn compile(&mut self) {
+        self.error = None;
+        if self.pattern.is_empty() {
+            self.matcher = None;
+            return;
+        }
+
+        let pattern = if self.use_regex {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+        match RegexBuilder::new(&pattern)
+            .case_insensitive(self.case_insensitive)
+            .build()
+        {
+            Ok(matcher) => self.matcher = Some(matcher),
+            Err(err) => {
+                self.matcher = None;
+                self.error = Some(err.to_string());
+            }
+        }
+    }
+
+    pub fn is_match(&self, message: &MessageInfo) -> bool {
+        self.matcher
+            .as_ref()
+            .is_some_and(|m| m.is_match(&message.display_text()))
+    }
+
+    /// Byte ranges of every match in `text`.
+    pub fn match_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        match &self.matcher {
+            Some(matcher) => matcher
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::twitch::helix::HELIX_URL;
+
+pub const CONFIG_PATH: &str = "config.toml";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    // one file per channel per day
+    Daily,
+    // a single file per channel that is never rotated
+    Never,
+}
+
+/// Client settings read from the `[options]` table of `config.toml`.
+///
+/// The irc crate already parses that table for us, so everything here is a
+/// plain string lookup with a default.
+#[derive(Clone)]
+pub struct Settings {
+    pub data_dir: PathBuf,
+    pub log_enabled: bool,
+    pub log_text: bool,
+    pub log_jsonl: bool,
+    pub log_rotation: LogRotation,
+    // delete log files older than this many days, 0 keeps everything
+    pub log_retention_days: u64,
+    pub log_excluded_channels: Vec<String>,
+    // log joins and parts, a line per viewer in big channels
+    pub log_membership: bool,
+    // how many sent messages are remembered across sessions
+    pub history_size: usize,
+    // the chat input grows with its text up to this many rows
+    pub input_max_lines: u16,
+    // `(mode, action, keys)` from `keymap.<mode>.<action> = "keys"` entries
+    pub keymap: Vec<(String, String, String)>,
+    // ask before leaving a channel
+    pub confirm_leave: bool,
+    // capture the mouse on startup, off keeps native text selection
+    pub mouse_capture: bool,
+    // split pane layout from the config, one saved with `save_layout` takes precedence
+    pub layout: Option<String>,
+    // lowercase words that put a message in the highlights tab
+    pub highlight_words: Vec<String>,
+    // twitch application client id, whispers can't be sent without one
+    pub client_id: Option<String>,
+    // overridable so the Helix client can be pointed at a local mock
+    pub helix_url: String,
+    // `nick` or `nick#channel` entries from the `ignore` list, see `saved_filters`
+    pub ignores: Vec<String>,
+    // show ignored messages as a placeholder line instead of hiding them
+    pub collapse_ignored: bool,
+    // `filter.<n> = "<action> <pattern>"` entries in order, see `FilterRule::parse`
+    pub filters: Vec<String>,
+    // near-identical messages this many seconds apart are folded together, 0 turns it off
+    pub collapse_window: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            data_dir: PathBuf::from(".twi-rs"),
+            log_enabled: true,
+            log_text: true,
+            log_jsonl: true,
+            log_rotation: LogRotation::Daily,
+            log_retention_days: 0,
+            log_excluded_channels: Vec::new(),
+            log_membership: false,
+            history_size: 1000,
+            input_max_lines: 5,
+            keymap: Vec::new(),
+            confirm_leave: true,
+            mouse_capture: true,
+            layout: None,
+            highlight_words: Vec::new(),
+            client_id: None,
+            helix_url: HELIX_URL.to_string(),
+            ignores: Vec::new(),
+            collapse_ignored: false,
+            filters: Vec::new(),
+            collapse_window: 30,
+        }
+    }
+}
+
+impl Settings {
+    pub fn from_options(options: &HashMap<String, String>) -> Self {
+        let mut settings = Self::default();
+
+        if let Some(dir) = options.get("data_dir") {
+            settings.data_dir = PathBuf::from(dir);
+        }
+        if let Some(enabled) = options.get("log_enabled") {
+            settings.log_enabled = parse_bool(enabled, settings.log_enabled);
+        }
+        if let Some(formats) = options.get("log_formats") {
+            let formats = split_list(formats);
+            settings.log_text = formats.iter().any(|f| f == "text");
+            settings.log_jsonl = formats.iter().any(|f| f == "jsonl");
+        }
+        if let Some(rotation) = options.get("log_rotation") {
+            settings.log_rotation = match rotation.as_str() {
+                "never" => LogRotation::Never,
+                _ => LogRotation::Daily,
+            };
+        }
+        if let Some(days) = options.get("log_retention_days") {
+            settings.log_retention_days = days.parse().unwrap_or(0);
+        }
+        if let Some(channels) = options.get("log_exclude") {
+            settings.log_excluded_channels = split_list(channels)
+                .into_iter()
+                .map(|c| normalize_channel(&c))
+                .collect();
+        }
+        if let Some(membership) = options.get("log_membership") {
+            settings.log_membership = parse_bool(membership, settings.log_membership);
+        }
+        if let Some(size) = options.get("history_size") {
+            settings.history_size = size.parse().unwrap_or(settings.history_size);
+        }
+        if let Some(lines) = options.get("input_max_lines") {
+            settings.input_max_lines = lines.parse().unwrap_or(settings.input_max_lines).max(1);
+        }
+        if let Some(confirm) = options.get("confirm_leave") {
+            settings.confirm_leave = parse_bool(confirm, settings.confirm_leave);
+        }
+        if let Some(mouse) = options.get("mouse_capture") {
+            settings.mouse_capture = parse_bool(mouse, settings.mouse_capture);
+        }
+        settings.layout = options.get("layout").cloned();
+        if let Some(words) = options.get("highlights") {
+            settings.highlight_words = split_list(&words.to_lowercase());
+        }
+        settings.client_id = options.get("client_id").cloned();
+        if let Some(url) = options.get("helix_url") {
+            settings.helix_url = url.clone();
+        }
+        if let Some(ignores) = options.get("ignore") {
+            settings.ignores = split_list(ignores);
+        }
+        if let Some(mode) = options.get("ignore_mode") {
+            settings.collapse_ignored = mode == "collapse";
+        }
+        if let Some(seconds) = options.get("collapse_window") {
+            settings.collapse_window = seconds.parse().unwrap_or(settings.collapse_window);
+        }
+        let mut filters: Vec<(usize, String)> = options
+            .iter()
+            .filter_map(|(key, rule)| {
+                let index = key.strip_prefix("filter.")?.parse().ok()?;
+                Some((index, rule.clone()))
+            })
+            .collect();
+        filters.sort();
+        settings.filters = filters.into_iter().map(|(_, rule)| rule).collect();
+        settings.keymap = options
+            .iter()
+            .filter_map(|(key, keys)| {
+                let (mode, action) = key.strip_prefix("keymap.")?.split_once('.')?;
+                Some((mode.to_string(), action.to_string(), keys.clone()))
+            })
+            .collect();
+        settings.keymap.sort();
+
+        settings
+    }
+
+    pub fn log_dir(&self) -> PathBuf {
+        self.data_dir.join("logs")
+    }
+
+    pub fn history_path(&self) -> PathBuf {
+        self.data_dir.join("history.jsonl")
+    }
+
+    pub fn layout_path(&self) -> PathBuf {
+        self.data_dir.join("layout")
+    }
+
+    /// The layout saved in the data dir, or the one written in the config, see `PaneLayout::parse`.
+    pub fn saved_layout(&self) -> Option<String> {
+        std::fs::read_to_string(self.layout_path())
+            .ok()
+            .map(|layout| layout.trim().to_string())
+            .or_else(|| self.layout.clone())
+    }
+
+    pub fn filters_path(&self) -> PathBuf {
+        self.data_dir.join("filters")
+    }
+
+    /// Ignores and filter rules saved in the data dir, or the ones written in the config.
+    ///
+    /// The file has an `ignore <nick>` line per ignore and a line per rule, in order.
+    pub fn saved_filters(&self) -> (Vec<String>, Vec<String>) {
+        let Ok(saved) = std::fs::read_to_string(self.filters_path()) else {
+            return (self.ignores.clone(), self.filters.clone());
+        };
+        let mut ignores = Vec::new();
+        let mut rules = Vec::new();
+        for line in saved.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            match line.strip_prefix("ignore ") {
+                Some(ignore) => ignores.push(ignore.trim().to_string()),
+                None => rules.push(line.to_string()),
+            }
+        }
+        (ignores, rules)
+    }
+
+    pub fn should_log(&self, channel: &str) -> bool {
+        self.log_enabled
+            && !self
+                .log_excluded_channels
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(channel))
+    }
+}
+
+fn parse_bool(value: &str, default: bool) -> bool {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => true,
+        "false" | "no" | "off" | "0" => false,
+        _ => default,
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+pub fn normalize_channel(channel: &str) -> String {
+    if channel.starts_with('#') {
+        channel.to_lowercase()
+    } else {
+        format!("#{}", channel.to_lowercase())
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Local};
+
+// how many minutes of message rate are kept for the sparkline
+const MINUTES: usize = 60;
+
+// words too common to say anything about the chat
+const STOP_WORDS: &[&str] = &[
+    "and", "are", "but", "for", "have", "just", "not", "that", "the", "this", "was", "what",
+    "with", "you",
+];
+
+fn minute(time: DateTime<Local>) -> i64 {
+    time.timestamp().div_euclid(60)
+}
+
+// most counted first, ties by name so the list doesn't jump around
+fn top(counts: &HashMap<String, u64>, limit: usize) -> Vec<(&str, u64)> {
+    let mut top: Vec<(&str, u64)> = counts
+        .iter()
+        .map(|(name, count)| (name.as_str(), *count))
+        .collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    top.truncate(limit);
+    top
+}
+
+/// What happened in one channel since it was joined.
+#[derive(Default)]
+pub struct ChannelStats {
+    // messages per minute, oldest first, the last entry is `last_minute`
+    per_minute: VecDeque<u64>,
+    last_minute: i64,
+    pub messages: u64,
+    chatters: HashMap<String, u64>,
+    emotes: HashMap<String, u64>,
+    words: HashMap<String, u64>,
+    pub subs: u64,
+    pub bits: u64,
+    cheerers: HashMap<String, u64>,
+}
+
+impl ChannelStats {
+    // moves the rate window forward to `time`, minutes without messages count as zero
+    fn advance(&mut self, time: DateTime<Local>) {
+        let now = minute(time);
+        if self.per_minute.is_empty() {
+            self.per_minute.push_back(0);
+        } else if now > self.last_minute {
+            let gap = (now - self.last_minute).min(MINUTES as i64);
+            self.per_minute.extend((0..gap).map(|_| 0));
+        } else {
+            // a late timestamp still counts towards the current minute
+            return;
+        }
+        self.last_minute = now;
+        while self.per_minute.len() > MINUTES {
+            self.per_minute.pop_front();
+        }
+    }
+
+    pub fn record_message(
+        &mut self,
+        nickname: &str,
+        content: &str,
+        emotes: &[String],
+        time: DateTime<Local>,
+    ) {
+        self.advance(time);
+        if let Some(current) = self.per_minute.back_mut() {
+            *current += 1;
+        }
+        self.messages += 1;
+        *self.chatters.entry(nickname.to_lowercase()).or_default() += 1;
+        for emote in emotes {
+            *self.emotes.entry(emote.clone()).or_default() += 1;
+        }
+        for word in content.split_whitespace() {
+            if emotes.iter().any(|emote| emote == word) || word.starts_with('@') {
+                continue;
+            }
+            let word = word
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            if word.chars().count() >= 3 && !STOP_WORDS.contains(&word.as_str()) {
+                *self.words.entry(word).or_default() += 1;
+            }
+        }
+    }
+
+    pub fn record_cheer(&mut self, nickname: &str, bits: u64) {
+        self.bits += bits;
+        *self.cheerers.entry(nickname.to_lowercase()).or_default() += bits;
+    }
+
+    /// Messages per minute up to `now`, oldest first.
+    pub fn per_minute(&self, now: DateTime<Local>) -> Vec<u64> {
+        let mut rate: Vec<u64> = self.per_minute.iter().copied().collect();
+        if !rate.is_empty() {
+            let gap = (minute(now) - self.last_minute).clamp(0, MINUTES as i64);
+            rate.extend((0..gap).map(|_| 0));
+        }
+        let skip = rate.len().saturating_sub(MINUTES);
+        rate.split_off(skip)
+    }
+
+    pub fn unique_chatters(&self) -> usize {
+        self.chatters.len()
+    }
+
+    pub fn top_chatters(&self, limit: usize) -> Vec<(&str, u64)> {
+        top(&self.chatters, limit)
+    }
+
+    pub fn top_emotes(&self, limit: usize) -> Vec<(&str, u64)> {
+        top(&self.emotes, limit)
+    }
+
+    /// Who cheered the most bits, with how many.
+    pub fn top_cheerers(&self, limit: usize) -> Vec<(&str, u64)> {
+        top(&self.cheerers, limit)
+    }
+
+    pub fn top_words(&self, limit: usize) -> Vec<(&str, u64)> {
+        top(&self.words, limit)
+    }
+}
+
+/// Session statistics for every channel, fed from the irc event stream.
+#[derive(Default)]
+pub struct Stats {
+    channels: HashMap<String, ChannelStats>,
+}
+
+impl Stats {
+    pub fn channel(&self, channel: &str) -> Option<&ChannelStats> {
+        self.channels.get(channel)
+    }
+
+    pub fn channel_mut(&mut self, channel: &str) -> &mut ChannelStats {
+        self.channels.entry(channel.to_string()).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    fn say(stats: &mut ChannelStats, nickname: &str, minutes: i64) {
+        stats.record_message(nickname, "hello there", &[], at(minutes));
+    }
+
+    #[test]
+    fn messages_are_counted_in_their_minute() {
+        let mut stats = ChannelStats::default();
+        say(&mut stats, "alice", 0);
+        say(&mut stats, "bob", 0);
+        say(&mut stats, "alice", 2);
+        assert_eq!(stats.per_minute(at(2)), [2, 0, 1]);
+        // quiet minutes since the last message show up as zeros
+        assert_eq!(stats.per_minute(at(4)), [2, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn late_timestamps_count_towards_the_current_minute() {
+        let mut stats = ChannelStats::default();
+        say(&mut stats, "alice", 3);
+        say(&mut stats, "bob", 1);
+        assert_eq!(stats.per_minute(at(3)), [2]);
+    }
+
+    #[test]
+    fn only_the_last_hour_is_kept() {
+        let mut stats = ChannelStats::default();
+        say(&mut stats, "alice", 0);
+        say(&mut stats, "alice", 1);
+        say(&mut stats, "alice", 61);
+        let rate = stats.per_minute(at(61));
+        assert_eq!(rate.len(), MINUTES);
+        assert_eq!(rate.iter().sum::<u64>(), 1);
+        assert_eq!(stats.per_minute(at(200)), vec![0; MINUTES]);
+        assert_eq!(stats.messages, 3);
+    }
+
+    #[test]
+    fn nothing_said_means_no_rate() {
+        assert!(ChannelStats::default().per_minute(at(0)).is_empty());
+    }
+
+    #[test]
+    fn own_messages_count_like_anyone_elses() {
+        let mut stats = ChannelStats::default();
+        say(&mut stats, "alice", 0);
+        // our own name as typed, and as twitch shows it
+        stats.record_message("tester", "Kappa hi @alice", &["Kappa".to_string()], at(0));
+        stats.record_message("Tester", "and the words", &[], at(1));
+        assert_eq!(stats.messages, 3);
+        assert_eq!(stats.per_minute(at(1)), [2, 1]);
+        assert_eq!(stats.unique_chatters(), 2);
+        assert_eq!(stats.top_chatters(1), [("tester", 2)]);
+        assert_eq!(stats.top_emotes(1), [("Kappa", 1)]);
+        assert_eq!(
+            stats.top_words(5),
+            [("hello", 1), ("there", 1), ("words", 1)]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::Path;
+
+use chrono::{Local, NaiveDate, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{app::MessageInfo, settings::normalize_channel};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS messages (
+    id INTEGER PRIMARY KEY,
+    message_id TEXT NOT NULL UNIQUE,
+    channel TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    nickname TEXT NOT NULL,
+    content TEXT NOT NULL,
+    sent_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS messages_channel_time ON messages (channel, sent_at);
+CREATE INDEX IF NOT EXISTS messages_user ON messages (user_id);
+CREATE INDEX IF NOT EXISTS messages_nickname ON messages (nickname COLLATE NOCASE);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+    content,
+    content='messages',
+    content_rowid='id'
+);
+CREATE TRIGGER IF NOT EXISTS messages_ai AFTER INSERT ON messages BEGIN
+    INSERT INTO messages_fts (rowid, content) VALUES (new.id, new.content);
+END;
+CREATE TRIGGER IF NOT EXISTS messages_ad AFTER DELETE ON messages BEGIN
+    INSERT INTO messages_fts (messages_fts, rowid, content) VALUES ('delete', old.id, old.content);
+END;
+";
+
+/// A message as it was stored, along with the channel it was sent to.
+#[derive(Clone)]
+pub struct StoredMessage {
+    pub channel: String,
+    pub message: MessageInfo,
+}
+
+/// Filters understood by `/search`.
+///
+/// Anything that is not a `key:value` filter is treated as full text.
+#[derive(Default, PartialEq, Eq, Debug)]
+pub struct SearchQuery {
+    pub text: String,
+    pub from: Option<String>,
+    pub channel: Option<String>,
+    pub before: Option<NaiveDate>,
+    pub after: Option<NaiveDate>,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Self {
+        let mut query = SearchQuery::default();
+        let mut words = Vec::new();
+
+        for word in input.split_whitespace() {
+            match word.split_once(':') {
+                Some(("from", nick)) if !nick.is_empty() => {
+                    query.from = Some(nick.trim_start_matches('@').to_string())
+                }
+                Some(("in", channel)) if !channel.is_empty() => {
+                    query.channel = Some(normalize_channel(channel))
+                }
+                Some(("before", date)) => match parse_date(date) {
+                    Some(date) => query.before = Some(date),
+                    None => words.push(word),
+                },
+                Some(("after", date)) => match parse_date(date) {
+                    Some(date) => query.after = Some(date),
+                    None => words.push(word),
+                },
+                _ => words.push(word),
+            }
+        }
+
+        query.text = words.join(" ");
+        query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == SearchQuery::default()
+    }
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+fn start_of_day(date: NaiveDate) -> i64 {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        .earliest()
+        .map(|t| t.timestamp_millis())
+        .unwrap_or_default()
+}
+
+// quote every word so user input can't be interpreted as fts5 syntax
+fn fts_query(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Local sqlite index of every message seen, searchable with fts5.
+pub struct MessageStore {
+    conn: Connection,
+}
+
+impl MessageStore {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        if let Some(dir) = path.parent() {
+            // let sqlite report the error if this fails
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let conn = Connection::open(path)?;
+        // every chat line is a write on the ui thread, so skip the fsync per commit
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn insert(&self, channel: &str, message: &MessageInfo) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO messages
+                (message_id, channel, user_id, nickname, content, sent_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                message.id,
+                channel,
+                message.user_id,
+                message.nickname,
+                message.content,
+                message.timestamp.timestamp_millis(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Newest matches first.
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<StoredMessage>> {
+        let mut sql = String::from(
+            "SELECT m.channel, m.message_id, m.user_id, m.nickname, m.content, m.sent_at
+             FROM messages m",
+        );
+        let mut conditions = Vec::new();
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if !query.text.is_empty() {
+            sql.push_str(" JOIN messages_fts f ON f.rowid = m.id");
+            conditions.push("messages_fts MATCH ?");
+            values.push(Box::new(fts_query(&query.text)));
+        }
+        if let Some(from) = &query.from {
+            conditions.push("m.nickname = ? COLLATE NOCASE");
+            values.push(Box::new(from.clone()));
+        }
+        if let Some(channel) = &query.channel {
+            conditions.push("m.channel = ? COLLATE NOCASE");
+            values.push(Box::new(channel.clone()));
+        }
+        if let Some(before) = query.before {
+            conditions.push("m.sent_at < ?");
+            values.push(Box::new(start_of_day(before)));
+        }
+        if let Some(after) = query.after {
+            // `after:` includes the whole day that was given
+            conditions.push("m.sent_at >= ?");
+            values.push(Box::new(start_of_day(after)));
+        }
+
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY m.sent_at DESC LIMIT ?");
+        values.push(Box::new(limit as i64));
+
+        let mut statement = self.conn.prepare(&sql)?;
+        let rows = statement.query_map(
+            rusqlite::params_from_iter(values.iter().map(|v| v.as_ref())),
+            row_to_message,
+        )?;
+        rows.collect()
+    }
+
+    /// Messages surrounding `message_id` in its channel, oldest first.
+    pub fn context(&self, message_id: &str, radius: usize) -> rusqlite::Result<Vec<StoredMessage>> {
+        let anchor: Option<(String, i64)> = self
+            .conn
+            .query_row(
+                "SELECT channel, sent_at FROM messages WHERE message_id = ?1",
+                params![message_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((channel, sent_at)) = anchor else {
+            return Ok(Vec::new());
+        };
+
+        let mut statement = self.conn.prepare(
+            "SELECT * FROM (
+                SELECT channel, message_id, user_id, nickname, content, sent_at FROM messages
+                WHERE channel = ?1 AND sent_at <= ?2 ORDER BY sent_at DESC LIMIT ?3
+             )
+             UNION
+             SELECT * FROM (
+                SELECT channel, message_id, user_id, nickname, content, sent_at FROM messages
+                WHERE channel = ?1 AND sent_at > ?2 ORDER BY sent_at ASC LIMIT ?4
+             )
+             ORDER BY sent_at ASC",
+        )?;
+        let rows = statement.query_map(
+            params![channel, sent_at, radius as i64 + 1, radius as i64],
+            row_to_message,
+        )?;
+        rows.collect()
+    }
+}
+
+fn row_to_message(row: &rusqlite::Row) -> rusqlite::Result<StoredMessage> {
+    let sent_at: i64 = row.get(5)?;
+    let channel: String = row.get(0)?;
+    Ok(StoredMessage {
+        channel: channel.clone(),
+        message: MessageInfo {
+            id: row.get(1)?,
+            channel,
+            user_id: row.get(2)?,
+            nickname: row.get(3)?,
+            content: row.get(4)?,
+            timestamp: Local
+                .timestamp_millis_opt(sent_at)
+                .single()
+                .unwrap_or_default(),
+            ..Default::default()
+        },
+    })
+}
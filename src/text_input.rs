@@ -0,0 +1,501 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// how many kills are remembered for ctrl-y / alt-y
+const KILL_RING_SIZE: usize = 16;
+// how many edits can be undone, the oldest are forgotten first
+const UNDO_LIMIT: usize = 100;
+
+#[derive(Clone, PartialEq, Eq)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum LastEdit {
+    Other,
+    Insert,
+    // kills in a row are appended to the same kill ring entry
+    Kill,
+    // the range of text just yanked, so alt-y can replace it
+    Yank(usize, usize),
+}
+
+/// Single line text input with readline style editing.
+///
+/// The cursor is a byte index into `text` that always sits on a grapheme
+/// cluster boundary, so emoji and combining marks move and delete as one.
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    kill_ring: Vec<String>,
+    // which kill ring entry the last yank used
+    yank_index: usize,
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    last_edit: LastEdit,
+}
+
+impl Default for TextInput {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            cursor: 0,
+            kill_ring: Vec::new(),
+            yank_index: 0,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: LastEdit::Other,
+        }
+    }
+}
+
+impl TextInput {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Length in chars, which is how twitch counts a message.
+    pub fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Hard wraps the text into rows at most `width` columns wide.
+    ///
+    /// Returns the rows and the cursor position as `(row, column)`.
+    pub fn wrap(&self, width: usize) -> (Vec<String>, (usize, usize)) {
+        let width = width.max(1);
+        let mut rows = vec![String::new()];
+        let mut row_width = 0;
+        let mut cursor = None;
+
+        for (index, grapheme) in self.text.grapheme_indices(true) {
+            let grapheme_width = grapheme.width();
+            if row_width + grapheme_width > width && row_width > 0 {
+                rows.push(String::new());
+                row_width = 0;
+            }
+            if index == self.cursor {
+                cursor = Some((rows.len() - 1, row_width));
+            }
+            rows.last_mut()
+                .expect("rows is never empty")
+                .push_str(grapheme);
+            row_width += grapheme_width;
+        }
+
+        let cursor = cursor.unwrap_or_else(|| {
+            // a cursor at the end of a full row goes on to the next one
+            if row_width >= width {
+                rows.push(String::new());
+                (rows.len() - 1, 0)
+            } else {
+                (rows.len() - 1, row_width)
+            }
+        });
+        (rows, cursor)
+    }
+
+    /// The part of the text that fits in `width` columns with the cursor in view,
+    /// along with the cursor's column within it.
+    pub fn scrolled(&self, width: usize) -> (String, usize) {
+        let width = width.max(1);
+        let mut start = 0;
+        // leave a column free for the cursor itself
+        while self.text[start..self.cursor].width() >= width {
+            start = self.next_boundary(start);
+        }
+
+        let mut visible = String::new();
+        let mut visible_width = 0;
+        for grapheme in self.text[start..].graphemes(true) {
+            visible_width += grapheme.width();
+            if visible_width > width {
+                break;
+            }
+            visible.push_str(grapheme);
+        }
+        (visible, self.text[start..self.cursor].width())
+    }
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    // start of the grapheme before `index`
+    fn previous_boundary(&self, index: usize) -> usize {
+        self.text[..index]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    // end of the grapheme after `index`
+    fn next_boundary(&self, index: usize) -> usize {
+        self.text[index..]
+            .graphemes(true)
+            .next()
+            .map_or(index, |g| index + g.len())
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.text.clone(),
+            cursor: self.cursor,
+        }
+    }
+
+    fn push_undo(&mut self, snapshot: Snapshot) {
+        self.undo_stack.push(snapshot);
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    // saves the current state for undo before an edit of the given kind
+    fn begin_edit(&mut self, kind: LastEdit) {
+        let coalesce = kind == LastEdit::Insert && self.last_edit == LastEdit::Insert;
+        if !coalesce {
+            self.push_undo(self.snapshot());
+        }
+        self.redo_stack.clear();
+        self.last_edit = kind;
+    }
+
+    // removes the text in `start..end` and returns it
+    fn remove_range(&mut self, start: usize, end: usize) -> String {
+        let removed: String = self.text.drain(start..end).collect();
+        self.cursor = start;
+        removed
+    }
+
+    // CURSOR MOVEMENT
+
+    pub fn move_cursor_left(&mut self) {
+        self.last_edit = LastEdit::Other;
+        self.cursor = self.previous_boundary(self.cursor);
+    }
+
+    pub fn move_cursor_right(&mut self) {
+        self.last_edit = LastEdit::Other;
+        self.cursor = self.next_boundary(self.cursor);
+    }
+
+    pub fn move_to_start(&mut self) {
+        self.last_edit = LastEdit::Other;
+        self.cursor = 0;
+    }
+
+    pub fn move_to_end(&mut self) {
+        self.last_edit = LastEdit::Other;
+        self.cursor = self.len();
+    }
+
+    fn word_start_before(&self, from: usize) -> usize {
+        let graphemes: Vec<(usize, &str)> = self.text[..from].grapheme_indices(true).collect();
+        let mut index = graphemes.len();
+        while index > 0 && !is_word(graphemes[index - 1].1) {
+            index -= 1;
+        }
+        while index > 0 && is_word(graphemes[index - 1].1) {
+            index -= 1;
+        }
+        graphemes.get(index).map_or(from, |(i, _)| *i)
+    }
+
+    fn word_end_after(&self, from: usize) -> usize {
+        let mut graphemes = self.text[from..].grapheme_indices(true).peekable();
+        while graphemes.next_if(|(_, g)| !is_word(g)).is_some() {}
+        while graphemes.next_if(|(_, g)| is_word(g)).is_some() {}
+        graphemes.peek().map_or(self.len(), |(i, _)| from + i)
+    }
+
+    pub fn move_word_left(&mut self) {
+        self.last_edit = LastEdit::Other;
+        self.cursor = self.word_start_before(self.cursor);
+    }
+
+    pub fn move_word_right(&mut self) {
+        self.last_edit = LastEdit::Other;
+        self.cursor = self.word_end_after(self.cursor);
+    }
+
+    // EDITING
+
+    pub fn enter_char(&mut self, new_char: char) {
+        self.begin_edit(LastEdit::Insert);
+        self.text.insert(self.cursor, new_char);
+        // a combining mark joins the grapheme before it, keep the cursor after both
+        self.cursor += new_char.len_utf8();
+    }
+
+    /// Deletes the grapheme before the cursor.
+    pub fn delete_char(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        self.begin_edit(LastEdit::Other);
+        self.remove_range(self.previous_boundary(self.cursor), self.cursor);
+    }
+
+    /// Deletes the grapheme under the cursor.
+    pub fn delete_char_forward(&mut self) {
+        if self.cursor >= self.len() {
+            return;
+        }
+        self.begin_edit(LastEdit::Other);
+        self.remove_range(self.cursor, self.next_boundary(self.cursor));
+    }
+
+    /// Start of the whitespace separated word that ends at the cursor.
+    pub fn word_start(&self) -> usize {
+        self.text[..self.cursor]
+            .rfind(char::is_whitespace)
+            .map_or(0, |i| {
+                i + self.text[i..].chars().next().map_or(1, char::len_utf8)
+            })
+    }
+
+    pub fn text_before_cursor(&self) -> &str {
+        &self.text[..self.cursor]
+    }
+
+    /// Replaces the text from `start` up to the cursor.
+    pub fn replace_before_cursor(&mut self, start: usize, replacement: &str) {
+        self.begin_edit(LastEdit::Other);
+        self.text.replace_range(start..self.cursor, replacement);
+        self.cursor = start + replacement.len();
+    }
+
+    /// Replaces all of the text, leaving the cursor at the end.
+    pub fn set_text(&mut self, text: String) {
+        self.begin_edit(LastEdit::Other);
+        self.text = text;
+        self.cursor = self.len();
+    }
+
+    /// Empties the input and forgets its undo history.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_edit = LastEdit::Other;
+    }
+
+    // KILL RING
+
+    fn kill(&mut self, start: usize, end: usize, prepend: bool) {
+        if start == end {
+            return;
+        }
+        let continuing = self.last_edit == LastEdit::Kill;
+        self.begin_edit(LastEdit::Kill);
+        let killed = self.remove_range(start, end);
+
+        match self.kill_ring.last_mut() {
+            Some(last) if continuing => {
+                if prepend {
+                    last.insert_str(0, &killed);
+                } else {
+                    last.push_str(&killed);
+                }
+            }
+            _ => {
+                self.kill_ring.push(killed);
+                if self.kill_ring.len() > KILL_RING_SIZE {
+                    self.kill_ring.remove(0);
+                }
+            }
+        }
+    }
+
+    /// Ctrl-W, kills the word before the cursor.
+    pub fn kill_word_back(&mut self) {
+        let start = self.word_start_before(self.cursor);
+        self.kill(start, self.cursor, true);
+    }
+
+    /// Alt-D, kills the word after the cursor.
+    pub fn kill_word_forward(&mut self) {
+        let end = self.word_end_after(self.cursor);
+        self.kill(self.cursor, end, false);
+    }
+
+    /// Ctrl-U, kills everything before the cursor.
+    pub fn kill_to_start(&mut self) {
+        self.kill(0, self.cursor, true);
+    }
+
+    /// Ctrl-K, kills everything after the cursor.
+    pub fn kill_to_end(&mut self) {
+        self.kill(self.cursor, self.len(), false);
+    }
+
+    fn insert_yank(&mut self, index: usize) {
+        let yanked = self.kill_ring[index].clone();
+        let start = self.cursor;
+        self.text.insert_str(start, &yanked);
+        self.cursor = start + yanked.len();
+        self.yank_index = index;
+        self.last_edit = LastEdit::Yank(start, self.cursor);
+    }
+
+    /// Ctrl-Y, inserts the most recent kill.
+    pub fn yank(&mut self) {
+        if self.kill_ring.is_empty() {
+            return;
+        }
+        self.begin_edit(LastEdit::Other);
+        self.insert_yank(self.kill_ring.len() - 1);
+    }
+
+    /// Alt-Y right after a yank, swaps it for the kill before it.
+    pub fn yank_pop(&mut self) {
+        let LastEdit::Yank(start, end) = self.last_edit else {
+            return;
+        };
+        self.remove_range(start, end);
+        let index = if self.yank_index == 0 {
+            self.kill_ring.len() - 1
+        } else {
+            self.yank_index - 1
+        };
+        self.insert_yank(index);
+    }
+
+    // UNDO
+
+    pub fn undo(&mut self) {
+        if let Some(snapshot) = self.undo_stack.pop() {
+            self.redo_stack.push(self.snapshot());
+            self.text = snapshot.text;
+            self.cursor = snapshot.cursor;
+        }
+        self.last_edit = LastEdit::Other;
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            self.push_undo(self.snapshot());
+            self.text = snapshot.text;
+            self.cursor = snapshot.cursor;
+        }
+        self.last_edit = LastEdit::Other;
+    }
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        text.chars().for_each(|c| input.enter_char(c));
+        input
+    }
+
+    #[test]
+    fn word_kills_stop_at_punctuation_and_join_in_the_kill_ring() {
+        let mut input = typed("hello big, world");
+        input.kill_word_back();
+        assert_eq!(input.text(), "hello big, ");
+        input.kill_word_back();
+        assert_eq!(input.text(), "hello ");
+        input.yank();
+        assert_eq!(input.text(), "hello big, world");
+    }
+
+    #[test]
+    fn word_kill_forward_takes_the_next_word() {
+        let mut input = typed("one two three");
+        input.move_to_start();
+        input.move_word_right();
+        input.kill_word_forward();
+        assert_eq!(input.text(), "one three");
+        input.kill_to_end();
+        input.move_to_start();
+        input.yank();
+        // kills in a row went into one entry
+        assert_eq!(input.text(), " two threeone");
+    }
+
+    #[test]
+    fn yank_pop_cycles_older_kills() {
+        let mut input = typed("first");
+        input.kill_to_start();
+        "second".chars().for_each(|c| input.enter_char(c));
+        input.kill_to_start();
+        input.yank();
+        assert_eq!(input.text(), "second");
+        input.yank_pop();
+        assert_eq!(input.text(), "first");
+    }
+
+    #[test]
+    fn typing_is_undone_in_one_step_and_redone() {
+        let mut input = typed("hi there");
+        input.kill_word_back();
+        input.undo();
+        assert_eq!(input.text(), "hi there");
+        input.undo();
+        assert_eq!(input.text(), "");
+        input.redo();
+        assert_eq!(input.text(), "hi there");
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        let mut input = typed("日本語");
+        assert_eq!(input.wrap(10), (vec!["日本語".to_string()], (0, 6)));
+        // a row of 5 columns fits two of them
+        assert_eq!(input.wrap(5).0, vec!["日本".to_string(), "語".to_string()]);
+        input.move_cursor_left();
+        assert_eq!(input.scrolled(10), ("日本語".to_string(), 4));
+    }
+
+    #[test]
+    fn combining_marks_move_and_delete_with_their_letter() {
+        let mut input = typed("cafe\u{301}!");
+        input.move_cursor_left();
+        input.move_cursor_left();
+        assert_eq!(input.text_before_cursor(), "caf");
+        assert_eq!(input.wrap(10).1, (0, 3));
+        input.delete_char_forward();
+        assert_eq!(input.text(), "caf!");
+    }
+
+    #[test]
+    fn joined_emoji_move_and_delete_as_one() {
+        // a family emoji joined with zero width joiners
+        let mut input = typed("a👨\u{200d}👩\u{200d}👧b");
+        input.move_cursor_left();
+        input.move_cursor_left();
+        assert_eq!(input.text_before_cursor(), "a");
+        input.move_cursor_right();
+        input.delete_char();
+        assert_eq!(input.text(), "ab");
+    }
+
+    #[test]
+    fn undo_history_is_limited() {
+        let mut input = TextInput::default();
+        for _ in 0..UNDO_LIMIT + 20 {
+            input.enter_char('a');
+            input.move_cursor_left();
+        }
+        while !input.undo_stack.is_empty() {
+            input.undo();
+        }
+        assert_eq!(input.text().len(), 20);
+    }
+}
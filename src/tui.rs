@@ -18,12 +18,18 @@ pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
     /// Terminal event handler.
     pub events: EventHandler,
+    /// Whether the terminal is currently reporting mouse events to us.
+    mouse_capture: bool,
 }
 
 impl<B: Backend> Tui<B> {
     /// Constructs a new instance of [`Tui`].
     pub fn new(terminal: Terminal<B>, events: EventHandler) -> Self {
-        Self { terminal, events }
+        Self {
+            terminal,
+            events,
+            mouse_capture: true,
+        }
     }
 
     /// Initializes the terminal interface.
@@ -74,6 +80,27 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 
+    /// Turns mouse capture on or off.
+    ///
+    /// With it off the terminal's own text selection works again.
+    pub fn set_mouse_capture(&mut self, enabled: bool) -> AppResult<()> {
+        if enabled == self.mouse_capture {
+            return Ok(());
+        }
+        if enabled {
+            ratatui::crossterm::execute!(io::stderr(), EnableMouseCapture)?;
+        } else {
+            ratatui::crossterm::execute!(io::stderr(), DisableMouseCapture)?;
+        }
+        self.mouse_capture = enabled;
+        Ok(())
+    }
+
+    /// The backend drawn to, a `TestBackend` holds the last rendered buffer.
+    pub fn backend(&self) -> &B {
+        self.terminal.backend()
+    }
+
     pub fn resize(&mut self) -> AppResult<()> {
         self.terminal.autoresize()?;
         Ok(())
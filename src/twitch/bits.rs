@@ -0,0 +1,78 @@
+use super::client_stream::Tags;
+
+/// Bits cheered with a message, from twitch's `bits` tag.
+pub fn message_bits(tags: &Tags) -> u64 {
+    tags.get("bits")
+        .and_then(|bits| bits.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Amount of a cheermote such as `Cheer100` or `BibleThump5000`.
+///
+/// Only meaningful in a message that carries bits, elsewhere such a word is
+/// just a word.
+pub fn cheermote_amount(word: &str) -> Option<u64> {
+    let digits = word.find(|c: char| c.is_ascii_digit())?;
+    let (prefix, amount) = word.split_at(digits);
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    amount.parse().ok().filter(|amount| *amount > 0)
+}
+
+/// Cheermote tier an amount falls in, 0 for 1 bit up to 4 for 10000 and more.
+pub fn cheer_tier(amount: u64) -> usize {
+    match amount {
+        0..=99 => 0,
+        100..=999 => 1,
+        1000..=4999 => 2,
+        5000..=9999 => 3,
+        _ => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cheermote_amounts() {
+        for (word, amount) in [
+            ("Cheer100", Some(100)),
+            ("BibleThump5000", Some(5000)),
+            ("cheer1", Some(1)),
+            ("cHeEr250", Some(250)),
+            ("CHEER10000", Some(10000)),
+            // only a whole word is a cheermote
+            ("Cheer100!", None),
+            ("Cheer100abc", None),
+            ("Cheer10x0", None),
+            ("100", None),
+            ("Cheer", None),
+            ("Cheer0", None),
+            ("Ch3er100", None),
+            ("@Cheer100", None),
+            ("", None),
+        ] {
+            assert_eq!(cheermote_amount(word), amount, "{word}");
+        }
+    }
+
+    #[test]
+    fn cheer_tier_edges() {
+        for (amount, tier) in [
+            (1, 0),
+            (99, 0),
+            (100, 1),
+            (999, 1),
+            (1000, 2),
+            (4999, 2),
+            (5000, 3),
+            (9999, 3),
+            (10000, 4),
+            (100000, 4),
+        ] {
+            assert_eq!(cheer_tier(amount), tier, "{amount}");
+        }
+    }
+}
@@ -1,15 +1,36 @@
-use irc::client::{prelude::*, ClientStream};
+use std::{collections::HashMap, net::SocketAddr};
 
-use crate::app::AppResult;
+use chrono::{DateTime, Local, TimeZone};
+use irc::{
+    client::{prelude::*, ClientStream},
+    proto::message::Tag,
+};
+
+use crate::app::{AppResult, Arrival, ReplyParent};
+
+// IRCv3 message tags, twitch sends these when the tags capability is requested
+pub type Tags = HashMap<String, String>;
 
 #[derive(Debug)]
 #[allow(dead_code)]
 pub enum ClientEvent {
-    // channel name, message content, source nickname(if it exists)
-    Privmsg(String, String, Option<String>),
-    // Channel name
-    Join(String),
-    Leave(String),
+    // channel name, message content, source nickname(if it exists), message tags
+    Privmsg(String, String, Option<String>, Tags),
+    // channel name, nickname of who joined or left
+    Join(String, String),
+    Leave(String, String),
+    // channel name, nicknames from a NAMES reply
+    Names(String, Vec<String>),
+    // sender nickname, message content, message tags
+    Whisper(String, String, Tags),
+    // channel name, message the user added if any, message tags with the `msg-id` kind
+    UserNotice(String, Option<String>, Tags),
+    // channel name or `*`, notice text, message tags with the `msg-id` kind
+    Notice(String, String, Tags),
+    // registration is done and channels can be joined
+    Welcome,
+    // twitch is about to close the connection and wants us to connect again
+    Reconnect,
     // Channel Name
     Ping(String),
     Other(Box<Message>),
@@ -17,26 +38,152 @@ pub enum ClientEvent {
 
 impl From<Message> for ClientEvent {
     fn from(message: Message) -> Self {
+        let tags = collect_tags(&message);
         match message.command {
             Command::PRIVMSG(ref channel, ref msg) => {
                 if let Some(nickname) = message.source_nickname() {
-                    ClientEvent::Privmsg(channel.clone(), msg.clone(), Some(nickname.to_string()))
+                    ClientEvent::Privmsg(
+                        channel.clone(),
+                        msg.clone(),
+                        Some(nickname.to_string()),
+                        tags,
+                    )
                 } else {
-                    ClientEvent::Privmsg(channel.clone(), msg.clone(), None)
+                    ClientEvent::Privmsg(channel.clone(), msg.clone(), None, tags)
                 }
             }
-            Command::JOIN(channel, _, _) => ClientEvent::Join(channel),
-            Command::PART(channel, _) => ClientEvent::Leave(channel),
+            Command::JOIN(ref channel, _, _) => ClientEvent::Join(
+                channel.clone(),
+                message.source_nickname().unwrap_or_default().to_string(),
+            ),
+            Command::PART(ref channel, _) => ClientEvent::Leave(
+                channel.clone(),
+                message.source_nickname().unwrap_or_default().to_string(),
+            ),
+            // `<me> = #channel :nick nick nick`
+            Command::Response(Response::RPL_NAMREPLY, ref args) if args.len() >= 4 => {
+                ClientEvent::Names(
+                    args[2].clone(),
+                    args[3].split_whitespace().map(String::from).collect(),
+                )
+            }
+            // `:nick!nick@nick.tmi.twitch.tv WHISPER <me> :message`
+            Command::Raw(ref command, ref args) if command == "WHISPER" && args.len() >= 2 => {
+                ClientEvent::Whisper(
+                    message.source_nickname().unwrap_or_default().to_string(),
+                    args[1].clone(),
+                    tags,
+                )
+            }
+            // subs, gifts, raids and other announcements
+            Command::Raw(ref command, ref args) if command == "USERNOTICE" && !args.is_empty() => {
+                ClientEvent::UserNotice(args[0].clone(), args.get(1).cloned(), tags)
+            }
+            Command::NOTICE(ref target, ref msg) => {
+                ClientEvent::Notice(target.clone(), msg.clone(), tags)
+            }
+            Command::Response(Response::RPL_WELCOME, _) => ClientEvent::Welcome,
+            Command::Raw(ref command, _) if command == "RECONNECT" => ClientEvent::Reconnect,
             Command::PING(server, _) => ClientEvent::Ping(server),
             _ => ClientEvent::Other(Box::new(message)),
         }
     }
 }
 
-pub async fn create_client_stream() -> AppResult<(Client, ClientStream)> {
-    let config = Config::load("config.toml").unwrap();
+fn collect_tags(message: &Message) -> Tags {
+    message
+        .tags
+        .iter()
+        .flatten()
+        .map(|Tag(key, value)| (key.clone(), value.clone().unwrap_or_default()))
+        .collect()
+}
+
+/// Time a message was sent, taken from twitch's `tmi-sent-ts` tag when present.
+pub fn message_time(tags: &Tags) -> DateTime<Local> {
+    tags.get("tmi-sent-ts")
+        .and_then(|ts| ts.parse::<i64>().ok())
+        .and_then(|ms| Local.timestamp_millis_opt(ms).single())
+        .unwrap_or_else(Local::now)
+}
+
+/// The message a reply answers, if this is a reply.
+pub fn reply_parent(tags: &Tags) -> Option<ReplyParent> {
+    let id = tags.get("reply-parent-msg-id")?.clone();
+    Some(ReplyParent {
+        thread_id: tags
+            .get("reply-thread-parent-msg-id")
+            .cloned()
+            .unwrap_or_else(|| id.clone()),
+        id,
+        nickname: tags
+            .get("reply-parent-display-name")
+            .or_else(|| tags.get("reply-parent-user-login"))
+            .cloned()
+            .unwrap_or_default(),
+        content: tags
+            .get("reply-parent-msg-body")
+            .cloned()
+            .unwrap_or_default(),
+    })
+}
+
+/// Whether this is the sender's first message in the channel, or their first in a while.
+pub fn arrival(tags: &Tags) -> Arrival {
+    if tags.get("first-msg").is_some_and(|flag| flag == "1") {
+        Arrival::FirstMessage
+    } else if tags
+        .get("returning-chatter")
+        .is_some_and(|flag| flag == "1")
+    {
+        Arrival::Returning
+    } else {
+        Arrival::Regular
+    }
+}
+
+/// Names of the emotes used in a message, from the `emotes` tag.
+///
+/// The tag looks like `25:0-4,12-16/1902:6-10`, with ranges given in chars.
+pub fn emote_names(content: &str, tags: &Tags) -> Vec<String> {
+    let Some(emotes) = tags.get("emotes") else {
+        return Vec::new();
+    };
+    let chars: Vec<char> = content.chars().collect();
+
+    let mut names: Vec<String> = emotes
+        .split('/')
+        .filter_map(|emote| emote.split_once(':'))
+        // every range of one emote has the same text, the first is enough
+        .filter_map(|(_, ranges)| ranges.split(',').next()?.split_once('-'))
+        .filter_map(|(start, end)| Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?)))
+        .filter(|(start, end)| start <= end && *end < chars.len())
+        .map(|(start, end)| chars[start..=end].iter().collect())
+        .collect();
+    names.dedup();
+    names
+}
 
+/// Connects to twitch, or to `server` instead of the configured address when given.
+///
+/// An injected server is spoken to in plain text, which is what a local
+/// test server expects.
+pub async fn create_client_stream(
+    mut config: Config,
+    server: Option<SocketAddr>,
+) -> AppResult<(Client, ClientStream)> {
+    if let Some(server) = server {
+        config.server = Some(server.ip().to_string());
+        config.port = Some(server.port());
+        config.use_tls = Some(false);
+    }
     let mut client = Client::from_config(config).await?;
+    client.send_cap_req(&[
+        Capability::Custom("twitch.tv/tags"),
+        Capability::Custom("twitch.tv/commands"),
+        // JOIN, PART and NAMES for other users
+        Capability::Custom("twitch.tv/membership"),
+    ])?;
     client.identify()?;
 
     let stream = client.stream()?;
@@ -0,0 +1,99 @@
+use std::error::Error;
+
+use serde::Deserialize;
+use serde_json::json;
+
+pub const HELIX_URL: &str = "https://api.twitch.tv/helix";
+
+// errors cross from spawned tasks back to the app, so they have to be Send
+pub type HelixResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Results of Helix requests made in the background.
+#[derive(Debug)]
+pub enum HelixEvent {
+    // login the whisper was sent to, and why it failed if it did
+    WhisperSent(String, Result<(), String>),
+}
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct User {
+    id: String,
+    login: String,
+}
+
+/// Minimal client for the parts of the Helix API that IRC can't do.
+///
+/// The base URL is configurable so it can be pointed at a local mock.
+#[derive(Clone)]
+pub struct HelixClient {
+    http: reqwest::Client,
+    base_url: String,
+    client_id: String,
+    token: String,
+}
+
+impl HelixClient {
+    pub fn new(base_url: &str, client_id: &str, token: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client_id: client_id.to_string(),
+            // the irc password is `oauth:<token>`
+            token: token.trim_start_matches("oauth:").to_string(),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.http
+            .request(method, format!("{}{path}", self.base_url))
+            .header("Client-Id", &self.client_id)
+            .bearer_auth(&self.token)
+    }
+
+    /// User ids for `logins`, in the same order.
+    pub async fn user_ids(&self, logins: &[&str]) -> HelixResult<Vec<String>> {
+        let query: Vec<(&str, &str)> = logins.iter().map(|login| ("login", *login)).collect();
+        let users: Data<User> = self
+            .request(reqwest::Method::GET, "/users")
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        logins
+            .iter()
+            .map(|login| {
+                users
+                    .data
+                    .iter()
+                    .find(|user| user.login.eq_ignore_ascii_case(login))
+                    .map(|user| user.id.clone())
+                    .ok_or_else(|| format!("no twitch user named {login}").into())
+            })
+            .collect()
+    }
+
+    /// Whispers `message` from `from_login` to `to_login`.
+    pub async fn send_whisper(
+        &self,
+        from_login: &str,
+        to_login: &str,
+        message: &str,
+    ) -> HelixResult<()> {
+        let ids = self.user_ids(&[from_login, to_login]).await?;
+        self.request(reqwest::Method::POST, "/whispers")
+            .query(&[("from_user_id", &ids[0]), ("to_user_id", &ids[1])])
+            .json(&json!({ "message": message }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
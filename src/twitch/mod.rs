@@ -1 +1,3 @@
+pub mod bits;
 pub mod client_stream;
+pub mod helix;
@@ -7,32 +7,134 @@ use ratatui::{
     Frame,
 };
 
+const JOIN_BOX_MIN_WIDTH: u16 = 30;
+
 /// Renders the user interface widgets.
 pub fn render(app: &mut App, frame: &mut Frame) {
+    if let AppMode::Joining = app.app_mode {
+        components::join::render_join_box(
+            app,
+            center(
+                frame.area(),
+                // never narrower than the hint above the input
+                Constraint::Length((frame.area().width * 2 / 5).max(JOIN_BOX_MIN_WIDTH)),
+                Constraint::Length(4),
+            ),
+            frame,
+        );
+        return;
+    }
+
     let vertical = Layout::vertical([
         Constraint::Length(1),
         Constraint::Min(1),
-        Constraint::Length(4),
+        Constraint::Length(components::input::input_height(app, frame.area().width)),
     ]);
 
     let [tabs_area, message_box_area, input_area] = vertical.areas(frame.area());
 
+    // the chat view stays visible underneath every other mode
     match app.app_mode {
-        AppMode::Normal => {
-            components::input::render_message_box(app, input_area, frame);
-            components::messages::render_messages(app, message_box_area, frame);
-            components::tabs::render_tabs(app, tabs_area, frame);
+        AppMode::BufferSearch => {
+            components::buffer_search::render_buffer_search(app, input_area, frame)
         }
+        _ => components::input::render_message_box(app, input_area, frame),
+    }
+    let message_box_area = if app.stats_visible {
+        let [messages_area, stats_area] =
+            Layout::horizontal([Constraint::Min(1), Constraint::Length(32)])
+                .areas(message_box_area);
+        components::stats::render_stats(app, stats_area, frame);
+        messages_area
+    } else {
+        message_box_area
+    };
+    let message_box_area = if app.chatter_panel.visible {
+        let [messages_area, chatters_area] =
+            Layout::horizontal([Constraint::Min(1), Constraint::Length(28)])
+                .areas(message_box_area);
+        components::chatters::render_chatters(app, chatters_area, frame);
+        messages_area
+    } else {
+        message_box_area
+    };
+    components::panes::render_panes(app, message_box_area, frame);
+    components::tabs::render_tabs(app, tabs_area, frame);
 
-        AppMode::Joining => components::join::render_join_box(
+    match app.app_mode {
+        AppMode::ConfirmLeave => components::confirm::render_confirm_leave(
+            app,
+            center(frame.area(), Constraint::Length(40), Constraint::Length(4)),
+            frame,
+        ),
+
+        AppMode::Help => components::help::render_help(
             app,
             center(
                 frame.area(),
-                Constraint::Percentage(40),
-                Constraint::Length(4),
+                Constraint::Percentage(70),
+                Constraint::Percentage(80),
             ),
             frame,
         ),
+
+        AppMode::Search => components::search::render_search(
+            app,
+            center(
+                frame.area(),
+                Constraint::Percentage(80),
+                Constraint::Percentage(80),
+            ),
+            frame,
+        ),
+
+        AppMode::Thread => components::thread::render_thread(
+            app,
+            center(
+                frame.area(),
+                Constraint::Percentage(70),
+                Constraint::Percentage(60),
+            ),
+            frame,
+        ),
+
+        AppMode::Filters => components::filters::render_filters(
+            app,
+            center(
+                frame.area(),
+                Constraint::Percentage(60),
+                Constraint::Percentage(60),
+            ),
+            frame,
+        ),
+
+        AppMode::ConfirmBan => components::confirm::render_confirm_ban(
+            app,
+            center(frame.area(), Constraint::Length(50), Constraint::Length(4)),
+            frame,
+        ),
+
+        AppMode::ChooseTarget => components::target_picker::render_target_picker(
+            app,
+            center(frame.area(), Constraint::Length(40), Constraint::Length(10)),
+            frame,
+        ),
+
+        AppMode::UserCard => components::user_card::render_user_card(
+            app,
+            center(
+                frame.area(),
+                Constraint::Percentage(60),
+                Constraint::Percentage(60),
+            ),
+            frame,
+        ),
+
+        AppMode::Normal
+        | AppMode::Joining
+        | AppMode::BufferSearch
+        | AppMode::Chatters
+        | AppMode::Moderate => {}
     }
 }
 
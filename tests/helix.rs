@@ -0,0 +1,114 @@
+//! Sends whispers through `HelixClient` pointed at a local stand-in for the Helix API.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+use twi_rs::twitch::helix::HelixClient;
+
+// method, path with query, authorization header and body of one request
+type Request = (String, String, String, String);
+
+// answers `/users` with an id for every login except `nobody`, and everything else with 204
+async fn mock_helix() -> (String, Arc<Mutex<Vec<Request>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let seen = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut buffer = Vec::new();
+            let mut chunk = [0; 4096];
+            // requests are small, read until the headers and the whole body are in
+            let (head, body) = loop {
+                let read = socket.read(&mut chunk).await.unwrap();
+                buffer.extend_from_slice(&chunk[..read]);
+                let text = String::from_utf8_lossy(&buffer).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|l| {
+                            l.to_lowercase()
+                                .strip_prefix("content-length: ")
+                                .map(String::from)
+                        })
+                        .and_then(|l| l.trim().parse().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length || read == 0 {
+                        break (head.to_string(), body.to_string());
+                    }
+                }
+            };
+
+            let mut request_line = head.lines().next().unwrap().split(' ');
+            let method = request_line.next().unwrap().to_string();
+            let path = request_line.next().unwrap().to_string();
+            let authorization = head
+                .lines()
+                .find_map(|l| l.strip_prefix("authorization: "))
+                .unwrap_or_default()
+                .to_string();
+
+            let response = if path.starts_with("/users") {
+                let users: Vec<String> = path
+                    .split(['?', '&'])
+                    .filter_map(|param| param.strip_prefix("login="))
+                    .filter(|login| *login != "nobody")
+                    .enumerate()
+                    .map(|(id, login)| format!(r#"{{"id":"{}","login":"{login}"}}"#, id + 1))
+                    .collect();
+                let json = format!(r#"{{"data":[{}]}}"#, users.join(","));
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{json}",
+                    json.len()
+                )
+            } else {
+                "HTTP/1.1 204 No Content\r\nconnection: close\r\n\r\n".to_string()
+            };
+            seen.lock()
+                .unwrap()
+                .push((method, path, authorization, body));
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+#[tokio::test]
+async fn sends_whispers_between_looked_up_users() {
+    let (url, requests) = mock_helix().await;
+    let helix = HelixClient::new(&url, "client", "oauth:secret");
+
+    helix
+        .send_whisper("me", "friend", "hi there")
+        .await
+        .unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    let (method, path, authorization, _) = &requests[0];
+    assert_eq!(method, "GET");
+    assert_eq!(path, "/users?login=me&login=friend");
+    assert_eq!(authorization, "Bearer secret");
+    let (method, path, _, body) = &requests[1];
+    assert_eq!(method, "POST");
+    assert_eq!(path, "/whispers?from_user_id=1&to_user_id=2");
+    assert_eq!(body, r#"{"message":"hi there"}"#);
+}
+
+#[tokio::test]
+async fn whispering_an_unknown_user_fails() {
+    let (url, requests) = mock_helix().await;
+    let helix = HelixClient::new(&url, "client", "oauth:secret");
+
+    let error = helix.send_whisper("me", "nobody", "hi").await.unwrap_err();
+
+    assert!(error.to_string().contains("nobody"));
+    // nothing to whisper to, so nothing was posted
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,210 @@
+//! A scriptable stand-in for twitch's chat server (TMI).
+//!
+//! It answers the handshake, `JOIN`, `PART` and `PING` the way twitch does,
+//! hands every line the client sends to the test, and lets the test push
+//! whatever it wants back, tags included.
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{tcp::OwnedWriteHalf, TcpListener, TcpStream},
+    sync::{mpsc, Mutex as AsyncMutex},
+    time::timeout,
+};
+
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+const HOST: &str = "tmi.twitch.tv";
+
+pub struct MockTmi {
+    pub addr: SocketAddr,
+    connections: mpsc::UnboundedReceiver<Connection>,
+}
+
+impl MockTmi {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, connections) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                if sender.send(Connection::serve(socket)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { addr, connections }
+    }
+
+    /// The next client connection.
+    pub async fn accept(&mut self) -> Connection {
+        timeout(TIMEOUT, self.connections.recv())
+            .await
+            .expect("no client connected")
+            .unwrap()
+    }
+}
+
+#[derive(Default)]
+struct State {
+    nickname: String,
+    // PRIVMSGs accepted before answering with `msg_ratelimit`
+    rate_limit: Option<usize>,
+    sent: usize,
+}
+
+/// One connected client.
+pub struct Connection {
+    writer: Arc<AsyncMutex<OwnedWriteHalf>>,
+    received: mpsc::UnboundedReceiver<String>,
+    state: Arc<Mutex<State>>,
+}
+
+// `:nick!nick@nick.tmi.twitch.tv`
+fn user_prefix(nickname: &str) -> String {
+    format!(":{nickname}!{nickname}@{nickname}.{HOST}")
+}
+
+// what twitch answers to a line from the client
+fn replies(line: &str, state: &Mutex<State>) -> Vec<String> {
+    let mut state = state.lock().unwrap();
+    let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+    match command {
+        "CAP" => match args.strip_prefix("REQ ") {
+            Some(caps) => vec![format!(":{HOST} CAP * ACK {caps}")],
+            None => Vec::new(),
+        },
+        "NICK" => {
+            state.nickname = args.to_string();
+            let nick = &state.nickname;
+            vec![
+                format!(":{HOST} 001 {nick} :Welcome, GLHF!"),
+                format!(":{HOST} 002 {nick} :Your host is {HOST}"),
+                format!(":{HOST} 003 {nick} :This server is rather new"),
+                format!(":{HOST} 004 {nick} :-"),
+                format!(":{HOST} 375 {nick} :-"),
+                format!(":{HOST} 372 {nick} :You are in a maze of twisty passages, all alike."),
+                format!(":{HOST} 376 {nick} :>"),
+            ]
+        }
+        "JOIN" => args
+            .split(',')
+            .flat_map(|channel| {
+                let nick = &state.nickname;
+                [
+                    format!("{} JOIN {channel}", user_prefix(nick)),
+                    format!(":{nick}.{HOST} 353 {nick} = {channel} :{nick}"),
+                    format!(":{nick}.{HOST} 366 {nick} {channel} :End of /NAMES list"),
+                ]
+            })
+            .collect(),
+        "PART" => vec![format!("{} PART {args}", user_prefix(&state.nickname))],
+        "PING" => vec![format!(":{HOST} PONG {HOST} {args}")],
+        "PRIVMSG" => {
+            state.sent += 1;
+            let channel = args.split(' ').next().unwrap_or_default();
+            match state.rate_limit {
+                Some(limit) if state.sent > limit => vec![format!(
+                    "@msg-id=msg_ratelimit :{HOST} NOTICE {channel} :Your message was not sent because you are sending messages too quickly."
+                )],
+                _ => Vec::new(),
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+impl Connection {
+    fn serve(socket: TcpStream) -> Self {
+        let (reader, writer) = socket.into_split();
+        let writer = Arc::new(AsyncMutex::new(writer));
+        let state = Arc::new(Mutex::new(State::default()));
+        let (sender, received) = mpsc::unbounded_channel();
+
+        let reply_writer = writer.clone();
+        let reply_state = state.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                for reply in replies(&line, &reply_state) {
+                    let mut writer = reply_writer.lock().await;
+                    if writer
+                        .write_all(format!("{reply}\r\n").as_bytes())
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+                // the test may have stopped listening, the replies still matter
+                let _ = sender.send(line);
+            }
+        });
+
+        Self {
+            writer,
+            received,
+            state,
+        }
+    }
+
+    /// Sends a raw line to the client.
+    pub async fn send(&self, line: &str) {
+        let mut writer = self.writer.lock().await;
+        writer
+            .write_all(format!("{line}\r\n").as_bytes())
+            .await
+            .unwrap();
+    }
+
+    /// Sends a chat message from `nickname`, `tags` given as `key=value;key=value`.
+    pub async fn privmsg(&self, tags: &str, nickname: &str, channel: &str, text: &str) {
+        self.send(&format!(
+            "@{tags} {} PRIVMSG {channel} :{text}",
+            user_prefix(nickname)
+        ))
+        .await;
+    }
+
+    /// Someone else joining `channel`.
+    pub async fn join(&self, nickname: &str, channel: &str) {
+        self.send(&format!("{} JOIN {channel}", user_prefix(nickname)))
+            .await;
+    }
+
+    /// Answers every PRIVMSG after the first `limit` with twitch's rate limit notice.
+    pub fn rate_limit(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.rate_limit = Some(limit);
+        state.sent = 0;
+    }
+
+    /// Asks the client to reconnect and closes the connection, like twitch before a restart.
+    pub async fn reconnect(&self) {
+        self.send(&format!(":{HOST} RECONNECT")).await;
+        self.writer.lock().await.shutdown().await.unwrap();
+    }
+
+    /// Waits for a line from the client starting with `prefix`, skipping everything before it.
+    ///
+    /// The irc crate leaves the `:` off a single word message, so match
+    /// messages with spaces in them to be sure of the format.
+    pub async fn expect(&mut self, prefix: &str) -> String {
+        let received = &mut self.received;
+        timeout(TIMEOUT, async {
+            loop {
+                let line = received.recv().await.expect("client disconnected");
+                if line.starts_with(prefix) {
+                    return line;
+                }
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("client never sent {prefix}"))
+    }
+}
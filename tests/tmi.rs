@@ -0,0 +1,322 @@
+//! Drives `App` against the mock chat server, without a terminal or network.
+
+mod mock_tmi;
+
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use irc::client::data::Config;
+use tokio::{sync::mpsc, time::timeout};
+use tokio_util::sync::CancellationToken;
+
+use mock_tmi::{Connection, MockTmi, TIMEOUT};
+use twi_rs::{
+    app::{App, Arrival},
+    chatters::Role,
+    event::{spawn_irc_reader, Event},
+    irc_handler::handle_irc_messages,
+    settings::Settings,
+    twitch::client_stream::create_client_stream,
+};
+
+const NICKNAME: &str = "tester";
+
+// every test gets its own store and history
+fn data_dir() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "twi-rs-test-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+struct Harness {
+    app: App,
+    events: mpsc::UnboundedReceiver<Event>,
+    server: MockTmi,
+    connection: Connection,
+    cancel_token: CancellationToken,
+    data_dir: PathBuf,
+}
+
+impl Harness {
+    async fn connect(channels: &[&str]) -> Self {
+        let mut server = MockTmi::start().await;
+        let config = Config {
+            nickname: Some(NICKNAME.to_string()),
+            password: Some("oauth:token".to_string()),
+            channels: channels.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        };
+        let (client, stream) = create_client_stream(config.clone(), Some(server.addr))
+            .await
+            .unwrap();
+        let connection = server.accept().await;
+
+        let data_dir = data_dir();
+        let settings = Settings {
+            data_dir: data_dir.clone(),
+            log_enabled: false,
+            ..Default::default()
+        };
+        let cancel_token = CancellationToken::new();
+        let app = App::new(client, settings, cancel_token.clone()).unwrap();
+        let (sender, events) = mpsc::unbounded_channel();
+        spawn_irc_reader(
+            config,
+            Some(server.addr),
+            stream,
+            sender,
+            cancel_token.clone(),
+        );
+
+        Self {
+            app,
+            events,
+            server,
+            connection,
+            cancel_token,
+            data_dir,
+        }
+    }
+
+    // hands one event to the app the way the main loop does, true if it was a reconnect
+    async fn handle_next(&mut self) -> bool {
+        match self.events.recv().await.expect("event stream closed") {
+            Event::Client(event) => handle_irc_messages(event, &mut self.app).unwrap(),
+            Event::Reconnected(client) => {
+                self.app.reconnected(*client);
+                return true;
+            }
+            Event::Disconnected => self.app.disconnected(),
+            Event::ReconnectFailed { error, retry_in } => {
+                self.app.reconnect_failed(error, retry_in)
+            }
+            _ => {}
+        }
+        false
+    }
+
+    async fn pump_until(&mut self, done: impl Fn(&App) -> bool) {
+        timeout(TIMEOUT, async {
+            while !done(&self.app) {
+                self.handle_next().await;
+            }
+        })
+        .await
+        .expect("the app never got there");
+    }
+
+    async fn pump_until_reconnected(&mut self) {
+        timeout(TIMEOUT, async { while !self.handle_next().await {} })
+            .await
+            .expect("the client never reconnected");
+    }
+
+    async fn join(channel: &str) -> Self {
+        let mut harness = Self::connect(&[channel]).await;
+        harness.connection.expect(&format!("JOIN {channel}")).await;
+        harness
+            .pump_until(|app| app.channels.iter().any(|c| c.name == channel))
+            .await;
+        harness
+    }
+
+    async fn send(&mut self, text: &str) {
+        self.app.message_box.set_input(text.to_string());
+        self.app.send_chat_message().unwrap();
+    }
+
+    // types `channel` into the join dialog and confirms it
+    fn join_channel(&mut self, channel: &str) {
+        self.app.join_box.channel.set_text(channel.to_string());
+        self.app.join_channel();
+    }
+
+    fn messages(&self, channel: &str) -> Vec<(String, String)> {
+        self.app
+            .channels
+            .iter()
+            .find(|c| c.name == channel)
+            .map(|c| {
+                c.messages
+                    .iter()
+                    .map(|m| (m.nickname.clone(), m.content.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+        let _ = std::fs::remove_dir_all(&self.data_dir);
+    }
+}
+
+#[tokio::test]
+async fn handshake_requests_twitch_capabilities() {
+    let mut harness = Harness::connect(&[]).await;
+
+    let caps = harness.connection.expect("CAP REQ").await;
+    assert!(caps.contains("twitch.tv/tags"));
+    assert!(caps.contains("twitch.tv/commands"));
+    assert!(caps.contains("twitch.tv/membership"));
+    harness.connection.expect("PASS oauth:token").await;
+    harness.connection.expect(&format!("NICK {NICKNAME}")).await;
+}
+
+#[tokio::test]
+async fn joins_configured_channels_and_tracks_chatters() {
+    let mut harness = Harness::join("#test").await;
+
+    harness.connection.join("viewer", "#test").await;
+    harness
+        .pump_until(|app| app.channels[0].chatters.get("viewer").is_some())
+        .await;
+
+    let chatters = &harness.app.channels[0].chatters;
+    assert!(chatters.get(NICKNAME).is_some());
+    assert_eq!(chatters.count(), 2);
+}
+
+#[tokio::test]
+async fn joins_from_the_dialog() {
+    let mut harness = Harness::join("#test").await;
+
+    harness.join_channel("other");
+    harness.connection.expect("JOIN #other").await;
+    harness.pump_until(|app| app.channels.len() == 2).await;
+
+    assert_eq!(harness.app.channels[1].name, "#other");
+}
+
+#[tokio::test]
+async fn shows_chat_with_its_tags() {
+    let mut harness = Harness::join("#test").await;
+
+    harness
+        .connection
+        .privmsg(
+            "badges=moderator/1;bits=100;first-msg=1;id=abc;tmi-sent-ts=1700000000000;user-id=42",
+            "viewer",
+            "#test",
+            "Cheer100 hello",
+        )
+        .await;
+    harness
+        .pump_until(|app| !app.channels[0].messages.is_empty())
+        .await;
+
+    let channel = &harness.app.channels[0];
+    let message = &channel.messages[0];
+    assert_eq!(message.id, "abc");
+    assert_eq!(message.user_id, "42");
+    assert_eq!(message.nickname, "viewer");
+    assert_eq!(message.content, "Cheer100 hello");
+    assert_eq!(message.bits, 100);
+    assert_eq!(message.arrival, Arrival::FirstMessage);
+    assert_eq!(message.timestamp.timestamp(), 1_700_000_000);
+    assert_eq!(
+        channel.chatters.get("viewer").unwrap().role,
+        Role::Moderator
+    );
+}
+
+#[tokio::test]
+async fn sends_chat_and_shows_it() {
+    let mut harness = Harness::join("#test").await;
+
+    harness.send("hello chat").await;
+
+    harness.connection.expect("PRIVMSG #test :hello chat").await;
+    assert_eq!(
+        harness.messages("#test"),
+        vec![(NICKNAME.to_string(), "hello chat".to_string())]
+    );
+    assert!(harness.app.message_box.input.is_empty());
+}
+
+#[tokio::test]
+async fn shows_rate_limit_notices() {
+    let mut harness = Harness::join("#test").await;
+    harness.connection.rate_limit(1);
+
+    harness.send("first try").await;
+    harness.send("second try").await;
+    harness.connection.expect("PRIVMSG #test :second try").await;
+    harness
+        .pump_until(|app| app.channels[0].messages.iter().any(|m| m.nickname == "*"))
+        .await;
+
+    let notice = harness.messages("#test").pop().unwrap();
+    assert!(notice.1.contains("sending messages too quickly"));
+}
+
+#[tokio::test]
+async fn reconnects_and_rejoins_when_asked() {
+    let mut harness = Harness::join("#test").await;
+    harness.join_channel("other");
+    harness.connection.expect("JOIN #other").await;
+    harness.pump_until(|app| app.channels.len() == 2).await;
+    harness
+        .connection
+        .privmsg("id=1", "viewer", "#test", "before")
+        .await;
+    harness
+        .pump_until(|app| !app.channels[0].messages.is_empty())
+        .await;
+
+    harness.connection.reconnect().await;
+    harness.connection = harness.server.accept().await;
+    harness.pump_until_reconnected().await;
+
+    harness.connection.expect("JOIN #test").await;
+    harness.connection.expect("JOIN #other").await;
+    harness
+        .connection
+        .privmsg("id=2", "viewer", "#test", "after")
+        .await;
+    harness
+        .pump_until(|app| {
+            app.channels[0]
+                .messages
+                .iter()
+                .any(|m| m.content == "after")
+        })
+        .await;
+    harness.send("back again").await;
+    harness.connection.expect("PRIVMSG #test :back again").await;
+
+    // the same tabs, with the scrollback from before
+    let names: Vec<&str> = harness
+        .app
+        .channels
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, ["#test", "#other"]);
+    let (notices, chat): (Vec<_>, Vec<_>) = harness
+        .messages("#test")
+        .into_iter()
+        .partition(|(nickname, _)| nickname == "*");
+    let contents: Vec<String> = chat.into_iter().map(|(_, content)| content).collect();
+    assert_eq!(contents, ["before", "after", "back again"]);
+    let notices: Vec<String> = notices.into_iter().map(|(_, content)| content).collect();
+    assert_eq!(
+        notices,
+        ["disconnected from twitch, reconnecting", "reconnected"]
+    );
+}
+
+#[tokio::test]
+async fn answers_pings_from_the_server() {
+    let mut harness = Harness::join("#test").await;
+
+    harness.connection.send("PING :tmi.twitch.tv").await;
+    harness.connection.expect("PONG").await;
+}
@@ -0,0 +1,742 @@
+//! Renders the app into ratatui's `TestBackend` and checks what ends up on screen.
+
+use std::{
+    cell::{Cell, RefCell},
+    io,
+    path::PathBuf,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use irc::proto::Message;
+use ratatui::{backend::TestBackend, style::Color, Terminal};
+use tokio_util::sync::CancellationToken;
+use unicode_width::UnicodeWidthStr;
+
+use twi_rs::{
+    app::{App, AppMode, ChatClient},
+    event::{Event, EventHandler},
+    filters::Ignore,
+    irc_handler::handle_irc_messages,
+    key_handler::handle_key_events,
+    messagebox::MessageMode,
+    mouse_handler::handle_mouse_events,
+    settings::Settings,
+    tui::Tui,
+    twitch::client_stream::{ClientEvent, Tags},
+};
+
+const NICKNAME: &str = "tester";
+
+/// Stands in for the irc connection, remembering what the app sent.
+struct OfflineClient {
+    sent: Rc<RefCell<Vec<String>>>,
+    // every send fails while set, like a dropped connection
+    broken: Rc<Cell<bool>>,
+}
+
+impl OfflineClient {
+    fn record(&self, line: String) -> irc::error::Result<()> {
+        if self.broken.get() {
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe).into());
+        }
+        self.sent.borrow_mut().push(line);
+        Ok(())
+    }
+}
+
+impl ChatClient for OfflineClient {
+    fn send(&self, message: Message) -> irc::error::Result<()> {
+        self.record(message.to_string().trim_end().to_string())
+    }
+
+    fn send_join(&self, channel: &str) -> irc::error::Result<()> {
+        self.record(format!("JOIN {channel}"))
+    }
+
+    fn send_part(&self, channel: &str) -> irc::error::Result<()> {
+        self.record(format!("PART {channel}"))
+    }
+
+    fn send_privmsg(&self, target: &str, message: &str) -> irc::error::Result<()> {
+        self.record(format!("PRIVMSG {target} :{message}"))
+    }
+
+    fn current_nickname(&self) -> &str {
+        NICKNAME
+    }
+}
+
+struct Harness {
+    app: App,
+    tui: Tui<TestBackend>,
+    sent: Rc<RefCell<Vec<String>>>,
+    broken: Rc<Cell<bool>>,
+    data_dir: PathBuf,
+}
+
+impl Harness {
+    fn new(width: u16, height: u16) -> Self {
+        Self::with_settings(width, height, |_| {})
+    }
+
+    // `configure` gets the settings before the app is built, with the data dir already created
+    fn with_settings(width: u16, height: u16, configure: impl FnOnce(&mut Settings)) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let data_dir = std::env::temp_dir().join(format!(
+            "twi-rs-tui-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let mut settings = Settings {
+            data_dir: data_dir.clone(),
+            log_enabled: false,
+            ..Default::default()
+        };
+        configure(&mut settings);
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let broken = Rc::new(Cell::new(false));
+        let client = OfflineClient {
+            sent: sent.clone(),
+            broken: broken.clone(),
+        };
+        let app = App::new(client, settings, CancellationToken::new()).unwrap();
+        let terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+
+        Self {
+            app,
+            tui: Tui::new(terminal, EventHandler::detached()),
+            sent,
+            broken,
+            data_dir,
+        }
+    }
+
+    // goes through the event queue and is handled like the main loop does
+    async fn feed(&mut self, event: Event) {
+        self.tui.events.sender().send(event).unwrap();
+        match self.tui.events.next().await.unwrap() {
+            Event::Client(event) => handle_irc_messages(event, &mut self.app).unwrap(),
+            Event::Key(key) => handle_key_events(key, &mut self.app).unwrap(),
+            Event::Mouse(mouse) => handle_mouse_events(mouse, &mut self.app).unwrap(),
+            Event::Helix(event) => self.app.on_helix_event(event),
+            Event::Reconnected(client) => self.app.reconnected(*client),
+            Event::Disconnected => self.app.disconnected(),
+            Event::ReconnectFailed { error, retry_in } => {
+                self.app.reconnect_failed(error, retry_in)
+            }
+            Event::Resize => self.tui.resize().unwrap(),
+        }
+    }
+
+    async fn joined(&mut self, channel: &str) {
+        self.feed(Event::Client(ClientEvent::Join(
+            channel.to_string(),
+            NICKNAME.to_string(),
+        )))
+        .await;
+    }
+
+    async fn chat(&mut self, channel: &str, nickname: &str, text: &str) {
+        self.chat_tagged(channel, nickname, text, &[]).await;
+    }
+
+    async fn chat_tagged(
+        &mut self,
+        channel: &str,
+        nickname: &str,
+        text: &str,
+        tags: &[(&str, &str)],
+    ) {
+        let tags: Tags = tags
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        self.feed(Event::Client(ClientEvent::Privmsg(
+            channel.to_string(),
+            text.to_string(),
+            Some(nickname.to_string()),
+            tags,
+        )))
+        .await;
+    }
+
+    async fn key(&mut self, code: KeyCode) {
+        self.feed(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+            .await;
+    }
+
+    async fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key(KeyCode::Char(c)).await;
+        }
+    }
+
+    // types `text` into the chat input and sends it
+    async fn command(&mut self, text: &str) {
+        self.app.message_box.mode = MessageMode::Editing;
+        self.type_text(text).await;
+        self.key(KeyCode::Enter).await;
+    }
+
+    fn contents(&self, tab: usize) -> Vec<String> {
+        self.app.channels[tab]
+            .messages
+            .iter()
+            .filter(|m| self.app.is_drawn(m))
+            .map(|m| m.content.clone())
+            .collect()
+    }
+
+    /// Draws a frame and returns it as one string per row.
+    fn render(&mut self) -> Vec<String> {
+        self.tui.draw(&mut self.app).unwrap();
+        let buffer = self.tui.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                let mut row = String::new();
+                let mut x = 0;
+                while x < buffer.area.width {
+                    let symbol = buffer[(x, y)].symbol();
+                    row.push_str(symbol);
+                    // a wide character also covers the cell after it
+                    x += symbol.width().max(1) as u16;
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// The text of every highlighted cell of the last frame, one string per row.
+    fn highlighted(&self) -> Vec<String> {
+        let buffer = self.tui.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| &buffer[(x, y)])
+                    .filter(|cell| cell.bg == Color::Yellow)
+                    .map(|cell| cell.symbol())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.data_dir);
+    }
+}
+
+#[tokio::test]
+async fn joined_channels_become_tabs() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#one").await;
+    harness.joined("#two").await;
+
+    assert_eq!(
+        harness.render(),
+        [
+            "#one #two                               ",
+            "┌messages──────────────────────────────┐",
+            "│                                      │",
+            "└──────────────────────────────────────┘",
+            "Press <ctrl-q> to exit, <i> to edit, <?>",
+            "┌Input─────────────────────────────────┐",
+            "│                                      │",
+            "└─────────────────────────────────0/500┘",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn chat_messages_are_drawn_bottom_up() {
+    let mut harness = Harness::new(40, 9);
+    harness.joined("#one").await;
+    harness.chat("#one", "viewer", "hello there").await;
+    harness.chat("#one", "other", "second message").await;
+
+    assert_eq!(
+        harness.render()[..5],
+        [
+            "#one                                    ",
+            "┌messages──────────────────────────────┐",
+            "│viewer: hello there                   │",
+            "│other: second message                 │",
+            "└──────────────────────────────────────┘",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn typed_text_shows_in_the_input() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#one").await;
+    harness.key(KeyCode::Char('i')).await;
+    harness.type_text("hi chat").await;
+
+    assert_eq!(
+        harness.render()[5..],
+        [
+            "┌Input─────────────────────────────────┐",
+            "│hi chat                               │",
+            "└─────────────────────────────────7/500┘",
+        ]
+    );
+
+    harness.key(KeyCode::Enter).await;
+    assert_eq!(*harness.sent.borrow(), ["PRIVMSG #one :hi chat"]);
+    assert_eq!(
+        harness.render()[6],
+        "│                                      │"
+    );
+}
+
+#[tokio::test]
+async fn join_dialog_takes_a_channel() {
+    let mut harness = Harness::new(40, 9);
+    harness.key(KeyCode::Char('\\')).await;
+    harness.type_text("abc").await;
+
+    assert_eq!(
+        harness.render(),
+        [
+            "                                        ",
+            "                                        ",
+            "                                        ",
+            "     Press <ESC> to return to chat      ",
+            "     ┌Join Channel────────────────┐     ",
+            "     │abc                         │     ",
+            "     └────────────────────────────┘     ",
+            "                                        ",
+            "                                        ",
+        ]
+    );
+
+    harness.key(KeyCode::Enter).await;
+    assert_eq!(*harness.sent.borrow(), ["JOIN #abc"]);
+}
+
+#[tokio::test]
+async fn long_messages_wrap_inside_the_block() {
+    let mut harness = Harness::new(24, 15);
+    harness.joined("#one").await;
+    harness
+        .chat(
+            "#one",
+            "viewer",
+            "the quick brown fox jumps over the lazy dog",
+        )
+        .await;
+    harness
+        .chat("#one", "viewer", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")
+        .await;
+    harness
+        .chat("#one", "viewer", "日本語のテキストです 😀 ok")
+        .await;
+
+    assert_eq!(
+        harness.render()[..11],
+        [
+            "#one                    ",
+            "┌messages──────────────┐",
+            "│viewer: the quick     │",
+            "│brown fox jumps over  │",
+            "│the lazy dog          │",
+            "│viewer:               │",
+            "│aaaaaaaaaaaaaaaaaaaaaa│",
+            "│aaaaaaaaa             │",
+            "│viewer: 日本語のテキス│",
+            "│トです 😀 ok          │",
+            "└──────────────────────┘",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn log_failures_are_shown_and_chat_goes_on() {
+    let mut harness = Harness::with_settings(40, 8, |settings| {
+        settings.log_enabled = true;
+        // a file where the log directory should be
+        std::fs::write(settings.log_dir(), "").unwrap();
+    });
+    harness.joined("#one").await;
+    harness.chat("#one", "viewer", "first one").await;
+    harness.chat("#one", "viewer", "second one").await;
+
+    let messages: Vec<String> = harness.app.channels[0]
+        .messages
+        .iter()
+        .map(|m| format!("{}: {}", m.nickname, m.content))
+        .collect();
+    assert_eq!(messages.len(), 3);
+    assert!(messages[0].starts_with("*: chat log not written"));
+    assert_eq!(messages[1..], ["viewer: first one", "viewer: second one"]);
+}
+
+#[tokio::test]
+async fn search_command_needs_its_own_word() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#one").await;
+    harness.key(KeyCode::Char('i')).await;
+    harness.type_text("/searchlight is a word").await;
+    harness.key(KeyCode::Enter).await;
+
+    assert_eq!(
+        *harness.sent.borrow(),
+        ["PRIVMSG #one :/searchlight is a word"]
+    );
+}
+
+#[tokio::test]
+async fn saved_layouts_reopen_their_tabs() {
+    let mut harness = Harness::with_settings(40, 8, |settings| {
+        settings.layout = Some("cols(50,[all],rows(50,#one,@friend))".to_string());
+    });
+    harness.feed(Event::Client(ClientEvent::Welcome)).await;
+    assert_eq!(*harness.sent.borrow(), ["JOIN #one"]);
+
+    harness.joined("#one").await;
+    let tabs: Vec<&str> = harness
+        .app
+        .channels
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(tabs, ["[all]", "@friend", "#one"]);
+}
+
+#[tokio::test]
+async fn broken_layouts_fall_back_to_one_pane() {
+    let mut harness = Harness::with_settings(40, 8, |settings| {
+        settings.layout = Some("cols(50,,#one)".to_string());
+    });
+    assert_eq!(harness.app.panes.count(), 1);
+
+    harness.joined("#one").await;
+    let notice = &harness.app.channels[0].messages[0];
+    assert!(notice.content.starts_with("saved layout ignored"));
+}
+
+#[tokio::test]
+async fn own_messages_are_recognised_in_any_casing() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#one").await;
+    harness.chat("#one", "Tester", "hi from the web").await;
+    harness.chat("#one", "someone", "hi").await;
+    assert_eq!(harness.app.channels[0].recent_chatters, ["someone"]);
+}
+
+#[tokio::test]
+async fn keymap_typos_keep_the_default_keys() {
+    let mut harness = Harness::with_settings(40, 8, |settings| {
+        settings.keymap = vec![
+            ("normal".into(), "qiut".into(), "x".into()),
+            ("normal".into(), "open_join".into(), "x".into()),
+        ];
+    });
+    harness.joined("#one").await;
+    let notice = &harness.app.channels[0].messages[0];
+    assert!(
+        notice.content.starts_with("keymap override ignored"),
+        "{}",
+        notice.content
+    );
+
+    // the override after the typo still applies
+    harness.key(KeyCode::Char('x')).await;
+    assert!(matches!(harness.app.app_mode, AppMode::Joining));
+}
+
+#[tokio::test]
+async fn repeats_stay_visible_when_the_first_sayer_is_ignored() {
+    let mut harness = Harness::new(40, 10);
+    harness.joined("#one").await;
+    harness.chat("#one", "spammer", "same old line").await;
+    harness.chat("#one", "viewer", "same old line").await;
+    assert_eq!(
+        harness.render()[4],
+        "│spammer: same old line ×2             │"
+    );
+
+    harness.app.filters.ignore(Ignore::new("spammer", None));
+    harness.app.refilter();
+    assert_eq!(
+        harness.render()[..5],
+        [
+            "#one                                    ",
+            "┌messages (1 filtered)─────────────────┐",
+            "│                                      │",
+            "│                                      │",
+            "│viewer: same old line                 │",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn cheers_and_first_messages_are_never_folded() {
+    let mut harness = Harness::new(40, 13);
+    harness.joined("#one").await;
+    harness.chat("#one", "viewer", "hype hype hype").await;
+    harness
+        .chat_tagged("#one", "cheerer", "hype hype hype", &[("bits", "100")])
+        .await;
+    harness
+        .chat_tagged("#one", "newcomer", "hype hype hype", &[("first-msg", "1")])
+        .await;
+
+    assert_eq!(
+        harness.render()[1..8],
+        [
+            "┌messages──────────────────────────────┐",
+            "│                                      │",
+            "│viewer: hype hype hype                │",
+            "│◆ cheered 100 bits                    │",
+            "│cheerer: hype hype hype               │",
+            "│★ first message from newcomer         │",
+            "│newcomer: hype hype hype              │",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn hidden_whispers_come_back_when_unignored() {
+    let mut harness = Harness::new(40, 8);
+    harness.app.filters.ignore(Ignore::new("pest", None));
+    harness
+        .feed(Event::Client(ClientEvent::Whisper(
+            "pest".to_string(),
+            "are you there".to_string(),
+            Tags::new(),
+        )))
+        .await;
+    assert!(harness.app.channels.is_empty());
+
+    harness.app.filters.unignore(&Ignore::new("pest", None));
+    harness.app.refilter();
+    let tab = &harness.app.channels[0];
+    assert_eq!(tab.name, "@pest");
+    assert_eq!(tab.messages[0].content, "are you there");
+}
+
+#[tokio::test]
+async fn reopening_a_rejoined_channel_focuses_its_tab() {
+    let mut harness = Harness::with_settings(40, 8, |settings| {
+        settings.confirm_leave = false;
+    });
+    harness.joined("#one").await;
+    harness.joined("#two").await;
+    // leaves #one, then joins it again before reopening it
+    harness.key(KeyCode::Char('x')).await;
+    harness.joined("#one").await;
+    harness.key(KeyCode::Char('u')).await;
+
+    let tabs: Vec<&str> = harness
+        .app
+        .channels
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(tabs, ["#two", "#one"]);
+    assert_eq!(harness.app.current_channel, 1);
+    assert_eq!(*harness.sent.borrow(), ["PART #one"]);
+}
+
+#[tokio::test]
+async fn moderation_says_what_was_sent_and_what_failed() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#chan").await;
+    harness.chat("#chan", "spammer", "buy followers").await;
+    harness.key(KeyCode::Char('M')).await;
+
+    harness.key(KeyCode::Char('2')).await;
+    assert_eq!(
+        harness.app.moderation_status.as_deref(),
+        Some("sent /timeout spammer 600")
+    );
+    assert_eq!(
+        *harness.sent.borrow(),
+        ["PRIVMSG #chan :/timeout spammer 600"]
+    );
+
+    harness.broken.set(true);
+    harness.key(KeyCode::Char('b')).await;
+    harness.key(KeyCode::Char('y')).await;
+    let status = harness.app.moderation_status.clone().unwrap();
+    assert!(status.starts_with("/ban spammer not sent"), "{status}");
+}
+
+#[tokio::test]
+async fn search_matches_cut_by_a_wrap_are_highlighted_on_both_rows() {
+    let mut harness = Harness::new(24, 12);
+    harness.joined("#chan").await;
+    harness
+        .chat("#chan", "viewer", "this line wraps right between the words")
+        .await;
+    harness.key(KeyCode::Char('/')).await;
+    harness.type_text("line wraps").await;
+    harness.render();
+    let highlighted = harness.highlighted();
+    assert_eq!(highlighted[4], "line");
+    assert_eq!(highlighted[5], "wraps");
+}
+
+#[tokio::test]
+async fn sent_messages_count_in_the_stats() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#one").await;
+    harness
+        .chat_tagged("#one", "viewer", "Kappa hi", &[("emotes", "25:0-4")])
+        .await;
+    harness.key(KeyCode::Char('i')).await;
+    harness.type_text("Kappa back").await;
+    harness.key(KeyCode::Enter).await;
+
+    let stats = harness.app.stats.channel("#one").unwrap();
+    assert_eq!(stats.messages, 2);
+    assert_eq!(stats.unique_chatters(), 2);
+    assert_eq!(stats.top_emotes(1), [("Kappa", 2)]);
+}
+
+#[tokio::test]
+async fn tabs_after_a_wide_title_are_clicked_where_they_are_drawn() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#one").await;
+    harness.key(KeyCode::Char('i')).await;
+    harness.type_text("/w 日本語").await;
+    harness.key(KeyCode::Enter).await;
+    harness.key(KeyCode::Esc).await;
+    harness.joined("#two").await;
+    assert!(harness.render()[0].starts_with("#one @日本語 #two"));
+
+    // `#two` starts after the six columns the whisper tab's title takes up
+    harness
+        .feed(Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 14,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        }))
+        .await;
+    assert_eq!(
+        harness.app.channels[harness.app.current_channel].name,
+        "#two"
+    );
+}
+
+#[tokio::test]
+async fn join_and_part_commands_need_a_channel_name() {
+    let mut harness = Harness::with_settings(40, 8, |settings| {
+        settings.confirm_leave = false;
+    });
+    harness.joined("#one").await;
+    harness.key(KeyCode::Char('i')).await;
+    harness.type_text("/join #").await;
+    harness.key(KeyCode::Enter).await;
+    assert!(harness.sent.borrow().is_empty());
+
+    harness.app.message_box.clear_box();
+    harness.type_text("/join Other").await;
+    harness.key(KeyCode::Enter).await;
+    harness.type_text("/part one").await;
+    harness.key(KeyCode::Enter).await;
+    assert_eq!(*harness.sent.borrow(), ["JOIN #other", "PART #one"]);
+}
+
+#[tokio::test]
+async fn ignores_typed_in_the_input_are_saved_in_the_data_dir() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#one").await;
+    harness.command("/ignore Pest").await;
+    harness.command("/ignore troll #one").await;
+    harness.chat("#one", "pest", "hello").await;
+    harness.chat("#one", "viewer", "hi").await;
+    assert_eq!(harness.contents(0), ["hi"]);
+
+    let saved = std::fs::read_to_string(harness.data_dir.join("filters")).unwrap();
+    assert_eq!(saved, "ignore pest\nignore troll#one\n");
+    let (ignores, rules) = harness.app.settings.saved_filters();
+    assert_eq!(ignores, ["pest", "troll#one"]);
+    assert!(rules.is_empty());
+
+    harness.command("/unignore pest").await;
+    assert_eq!(harness.contents(0), ["hello", "hi"]);
+}
+
+#[tokio::test]
+async fn filters_typed_in_the_input_are_saved_and_removed() {
+    let mut harness = Harness::new(40, 8);
+    harness.joined("#one").await;
+    harness.command("/filter hide spoiler alert").await;
+    harness
+        .chat("#one", "viewer", "SPOILER ALERT he dies")
+        .await;
+    harness.chat("#one", "viewer", "no spoilers").await;
+    assert_eq!(harness.contents(0), ["no spoilers"]);
+    let (_, rules) = harness.app.settings.saved_filters();
+    assert_eq!(rules, ["hide spoiler alert"]);
+
+    harness.command("/unfilter spoiler alert").await;
+    assert_eq!(
+        harness.contents(0),
+        ["SPOILER ALERT he dies", "no spoilers"]
+    );
+    let (_, rules) = harness.app.settings.saved_filters();
+    assert!(rules.is_empty());
+}
+
+#[tokio::test]
+async fn failing_filter_saves_are_reported_once() {
+    let mut harness = Harness::new(40, 8);
+    // a file where the data dir should be
+    let blocked = harness.data_dir.join("blocked");
+    std::fs::write(&blocked, "").unwrap();
+    harness.app.settings.data_dir = blocked.join("data");
+    harness.joined("#one").await;
+
+    harness.command("/ignore pest").await;
+    harness.command("/filter dim lol").await;
+    assert!(harness.app.running);
+    let notices: Vec<String> = harness
+        .contents(0)
+        .into_iter()
+        .filter(|c| c.starts_with("ignores and filters not saved"))
+        .collect();
+    assert_eq!(notices.len(), 1);
+}
+
+#[tokio::test]
+async fn failed_sends_keep_the_message_and_the_tab() {
+    let mut harness = Harness::with_settings(40, 8, |settings| {
+        settings.confirm_leave = false;
+    });
+    harness.joined("#one").await;
+    harness.broken.set(true);
+
+    harness.command("still there?").await;
+    assert_eq!(harness.app.message_box.input.text(), "still there?");
+    harness.app.message_box.mode = MessageMode::Normal;
+    harness.key(KeyCode::Char('x')).await;
+
+    assert_eq!(harness.app.channels.len(), 1);
+    let contents = harness.contents(0);
+    assert!(contents[0].starts_with("message not sent"), "{contents:?}");
+    assert!(contents[1].starts_with("#one not left"), "{contents:?}");
+}
+
+#[tokio::test]
+async fn moderation_hint_lists_every_moderation_key() {
+    let mut harness = Harness::new(200, 8);
+    harness.joined("#chan").await;
+    harness.chat("#chan", "viewer", "hi").await;
+    harness.key(KeyCode::Char('M')).await;
+
+    let hint = harness.render()[4].trim_end().to_string();
+    assert_eq!(
+        hint,
+        "moderating: <d> delete, <1> timeout 1m, <2> timeout 10m, <3> timeout 1h, <4> timeout 1d, \
+         <b> ban, <y> copy name, <r> reply, <t> thread, <x> repeats, <esc> done"
+    );
+}